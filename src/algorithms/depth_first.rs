@@ -1,68 +1,91 @@
-use super::{Solution, Solver};
+use super::{
+    steps::{trace_back, Event, Search},
+    Solution, Solver,
+};
 use crate::{
+    maze::Maze,
     node::{
         Node,
         NodeType::{self, Exit, Start},
     },
-    utils::get_node,
 };
-use std::collections::VecDeque;
 
 pub struct DepthFirst;
 
 impl Solver for DepthFirst {
-    fn solve(maze: &crate::maze::Maze) -> Option<Solution> {
+    fn search<'a>(&self, maze: &'a Maze) -> Box<dyn Search<'a> + 'a> {
         let width = maze.width;
         let total = maze.height * maze.width;
 
         let start = &maze.data[&Start];
         let end = &maze.data[&Exit];
 
-        let mut queue = Vec::new();
-        queue.push(start);
-
         let mut visited: Vec<bool> = (0..total).map(|_| false).collect();
-        let mut reverse_path: Vec<Option<&Node>> = (0..total).map(|_| None).collect();
-
-        let mut decisions = 0;
-
         let start_index = (start.point.y * width) + start.point.x;
         visited[start_index as usize] = true;
 
-        while let Some(current) = queue.pop() {
-            decisions += 1;
-            if current.point == end.point {
-                break;
-            }
+        Box::new(DepthFirstSearch {
+            maze,
+            end,
+            stack: Vec::from([start]),
+            visited,
+            reverse_path: (0..total).map(|_| None).collect(),
+            decisions: 0,
+        })
+    }
+}
 
-            for (_, next_point) in current.children.iter() {
-                let n_index = (next_point.y * width) + next_point.x;
+struct DepthFirstSearch<'a> {
+    maze: &'a Maze,
+    end: &'a Node,
+    stack: Vec<&'a Node>,
+    visited: Vec<bool>,
+    reverse_path: Vec<Option<&'a Node>>,
+    decisions: u32,
+}
 
-                if !visited[n_index as usize] {
-                    let node = maze
-                        .data
-                        .get(&NodeType::Path(*next_point))
-                        .or(maze.data.get(&NodeType::Exit))
-                        .unwrap();
-                    queue.push(node);
-                    visited[n_index as usize] = true;
-                    reverse_path[n_index as usize] = Some(current);
-                }
-            }
+impl<'a> Search<'a> for DepthFirstSearch<'a> {
+    fn step(&mut self, events: &mut Vec<Event>) -> bool {
+        let Some(current) = self.stack.pop() else {
+            return false;
+        };
+        self.decisions += 1;
+        if current.point == self.end.point {
+            events.push(Event::Found(current.point));
+            return false;
         }
-        let mut current = Some(&end);
-        let mut solution = VecDeque::new();
-
-        while current.is_some() {
-            let node = current.unwrap();
-            let node = get_node(node, maze).unwrap();
+        events.push(Event::Expanded(current.point));
 
-            solution.push_back(node);
+        let width = self.maze.width;
+        for (_, next_point) in current.children.iter() {
+            let n_index = (next_point.y * width) + next_point.x;
 
-            let index = (node.point.y * width) + node.point.x;
-            current = reverse_path.get(index as usize).unwrap().as_ref();
+            if !self.visited[n_index as usize] {
+                let node = self
+                    .maze
+                    .data
+                    .get(&NodeType::Path(*next_point))
+                    .or(self.maze.data.get(&NodeType::Exit))
+                    .unwrap();
+                self.stack.push(node);
+                self.visited[n_index as usize] = true;
+                self.reverse_path[n_index as usize] = Some(current);
+                events.push(Event::Pushed(*next_point));
+            }
         }
+        true
+    }
 
-        Some(Solution::new(decisions, solution))
+    fn decisions(&self) -> u32 {
+        self.decisions
+    }
+
+    fn solution(&self) -> Option<Solution<'a>> {
+        let end_index = (self.end.point.y * self.maze.width) + self.end.point.x;
+        if !self.visited[end_index as usize] {
+            return None;
+        }
+        let path = trace_back(self.maze, self.end, &self.reverse_path);
+        Some(Solution::new(self.maze, self.decisions, path))
     }
 }
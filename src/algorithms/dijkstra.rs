@@ -1,37 +1,31 @@
 pub struct Dijkstra;
 
-use crate::{
-    node::{
-        Node,
-        NodeType::{self, Exit, Start},
-        Point,
-    },
-    utils::{get_dist, get_node},
-};
-use std::{
-    cell::Cell,
-    collections::{BinaryHeap, VecDeque},
+use crate::node::{
+    Node,
+    NodeType::{self, Exit, Start},
+    Point,
 };
+use std::collections::BinaryHeap;
 
 use crate::{
-    algorithms::{Solution, Solver},
+    algorithms::{
+        steps::{trace_back, Event, Search, Unsolvable},
+        Solution, Solver,
+    },
     maze::Maze,
 };
 
+// An entry of the frontier. Entries are never updated in place, a shorter distance pushes another
+// entry and the old one is skipped when it comes up, as its node has been visited by then.
 #[derive(Debug, Eq, PartialEq)]
 struct BinNode {
     pub cost: u32,
     pub position: Point,
-    is_valid: Cell<bool>,
 }
 
 impl BinNode {
     pub fn new(cost: u32, position: Point) -> Self {
-        BinNode {
-            cost,
-            position,
-            is_valid: Cell::new(true),
-        }
+        BinNode { cost, position }
     }
 }
 
@@ -51,21 +45,13 @@ impl PartialOrd for BinNode {
 }
 
 impl Solver for Dijkstra {
-    fn solve(maze: &Maze) -> Option<Solution> {
-        let mut decisions = 0;
-
+    fn search<'a>(&self, maze: &'a Maze) -> Box<dyn Search<'a> + 'a> {
         let width = maze.width;
         let total = maze.height * maze.width;
 
-        let mut visited: Vec<bool> = (0..total).map(|_| false).collect();
-        let mut reverse_path: Vec<Option<&Node>> = (0..total).map(|_| None).collect();
-
-        if !maze.data.contains_key(&Start) {
-            return None;
-        }
-
-        let start = &maze.data[&Start];
-        let end = &maze.data[&Exit];
+        let (Some(start), Some(end)) = (maze.data.get(&Start), maze.data.get(&Exit)) else {
+            return Box::new(Unsolvable);
+        };
 
         let start_index = (start.point.y * width) + start.point.x;
 
@@ -75,76 +61,90 @@ impl Solver for Dijkstra {
         let mut unvisited = BinaryHeap::new();
         unvisited.push(BinNode::new(0, start.point));
 
-        while let Some(BinNode {
-            cost: _,
-            position,
-            is_valid,
-        }) = unvisited.pop()
-        {
-            decisions += 1;
+        Box::new(DijkstraSearch {
+            maze,
+            end,
+            unvisited,
+            distances,
+            visited: (0..total).map(|_| false).collect(),
+            reverse_path: (0..total).map(|_| None).collect(),
+            decisions: 0,
+        })
+    }
+}
 
-            if position == end.point {
-                break;
-            }
+struct DijkstraSearch<'a> {
+    maze: &'a Maze,
+    end: &'a Node,
+    unvisited: BinaryHeap<BinNode>,
+    distances: Vec<u32>,
+    visited: Vec<bool>,
+    reverse_path: Vec<Option<&'a Node>>,
+    decisions: u32,
+}
 
-            if !is_valid.take() {
-                continue;
-            }
+impl<'a> Search<'a> for DijkstraSearch<'a> {
+    fn step(&mut self, events: &mut Vec<Event>) -> bool {
+        let Some(BinNode { position, .. }) = self.unvisited.pop() else {
+            return false;
+        };
+        self.decisions += 1;
 
-            let node = maze
-                .data
-                .get(&NodeType::Path(position))
-                .or(maze.data.get(&NodeType::Start))
-                .unwrap();
-
-            let c_index = (position.y * width) + position.x;
-
-            // Iterate through each conneting child node
-            for (_, next_point) in node.children.iter() {
-                let n_index = (next_point.y * width) + next_point.x;
-
-                // If we havnt checked the current no;
-                if !visited[n_index as usize] {
-                    // Calculate the manhattan distance to the start node
-                    let abs_distance = get_dist(&position, next_point);
-
-                    // Get the current nodes current distance and add on the new distance
-                    let mut new_distance = distances[c_index as usize] + abs_distance;
-
-                    // if this new distance is the shortest path
-                    if new_distance < distances[n_index as usize] {
-                        reverse_path[n_index as usize] = Some(node);
-                    } else {
-                        let node = unvisited
-                            .iter()
-                            .find(|n| &n.position == next_point)
-                            .unwrap();
-                        node.is_valid.set(false);
-                        new_distance += node.cost;
-                    }
-                    let node = BinNode::new(new_distance, *next_point);
-                    unvisited.push(node);
-                    distances[n_index as usize] = new_distance;
-                }
-            }
+        let maze = self.maze;
+        let width = maze.width;
+        let c_index = (position.y * width) + position.x;
 
-            visited[c_index as usize] = true;
+        // A stale entry, the node was reached over a shorter path before
+        if self.visited[c_index as usize] {
+            return true;
         }
 
-        let mut current = Some(&end);
-        let mut solution = VecDeque::new();
-
-        while current.is_some() {
-            let node = current.unwrap();
-            let node = get_node(node, maze).unwrap();
+        self.visited[c_index as usize] = true;
+        if position == self.end.point {
+            events.push(Event::Found(position));
+            return false;
+        }
+        events.push(Event::Expanded(position));
+
+        let node = maze
+            .data
+            .get(&NodeType::Path(position))
+            .or(maze.data.get(&NodeType::Start))
+            .unwrap();
+
+        // Iterate through each conneting child node
+        for (_, next_point) in node.children.iter() {
+            let n_index = (next_point.y * width) + next_point.x;
+            if self.visited[n_index as usize] {
+                continue;
+            }
 
-            solution.push_back(node);
+            // The length of the edge, around its corners when the maze has been simplified
+            let new_distance =
+                self.distances[c_index as usize] + maze.distance(position, *next_point);
 
-            let index = (node.point.y * width) + node.point.x;
-            current = reverse_path.get(index as usize).unwrap().as_ref();
+            // Only a shorter path replaces the one found so far
+            if new_distance < self.distances[n_index as usize] {
+                self.distances[n_index as usize] = new_distance;
+                self.reverse_path[n_index as usize] = Some(node);
+                self.unvisited.push(BinNode::new(new_distance, *next_point));
+                events.push(Event::Pushed(*next_point));
+            }
         }
+        true
+    }
+
+    fn decisions(&self) -> u32 {
+        self.decisions
+    }
 
-        Some(Solution::new(decisions, solution))
+    fn solution(&self) -> Option<Solution<'a>> {
+        let end_index = (self.end.point.y * self.maze.width) + self.end.point.x;
+        if !self.visited[end_index as usize] {
+            return None;
+        }
+        let path = trace_back(self.maze, self.end, &self.reverse_path);
+        Some(Solution::new(self.maze, self.decisions, path))
     }
 }
 
@@ -152,31 +152,11 @@ impl Solver for Dijkstra {
 mod test {
 
     use super::*;
-    use crate::Image;
-    use image::Rgb;
-    use image::RgbImage;
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+    use crate::utils::get_dist;
     use pretty_assertions::assert_eq;
-
-    const WALL: Rgb<u8> = Rgb([0, 0, 0]);
-    const PATH: Rgb<u8> = Rgb([255, 255, 255]);
-
-    macro_rules! maze_image {
-        ($num:expr) => {{
-            let mut img = RgbImage::new($num[0].len() as u32, $num.len() as u32);
-            for (y, row) in $num.iter().enumerate() {
-                for (x, item) in row.iter().enumerate() {
-                    // if path
-                    if *item == 1 {
-                        img.put_pixel(x as u32, y as u32, PATH);
-                    // if wall
-                    } else {
-                        img.put_pixel(x as u32, y as u32, WALL);
-                    }
-                }
-            }
-            Image { image: img }
-        }};
-    }
+    use std::collections::VecDeque;
 
     fn create_path<'a>(coords: &'a [(u32, u32)], maze: &'a Maze) -> VecDeque<&'a Node> {
         let mut path = VecDeque::new();
@@ -215,12 +195,26 @@ mod test {
             [0, 1, 0, 0, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(1, 2)], &maze);
         assert_eq!(path, solution.path)
     }
 
+    #[test]
+    fn exit_out_of_reach() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 1, 0, 0],
+            [0, 0, 0, 0, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 1, 0]
+        ]);
+
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        assert!(Dijkstra.solve(&maze).is_none());
+    }
+
     #[test]
     fn maze_with_loop() {
         let img = maze_image!([
@@ -231,8 +225,8 @@ mod test {
             [0, 1, 0, 0, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(1, 1), (1, 3)], &maze);
         assert_eq!(path, solution.path)
     }
@@ -247,8 +241,8 @@ mod test {
             [0, 0, 0, 1, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(1, 1), (1, 3), (3, 3)], &maze);
         assert_eq!(path, solution.path)
     }
@@ -263,8 +257,8 @@ mod test {
             [0, 1, 0, 0, 0, 0, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(5, 3), (3, 3), (3, 1), (1, 1)], &maze);
         assert_eq!(path, solution.path)
     }
@@ -279,8 +273,8 @@ mod test {
             [0, 0, 1, 0, 0, 0, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(3, 1), (1, 1), (1, 3), (2, 3)], &maze);
         assert_eq!(path, solution.path)
     }
@@ -295,8 +289,8 @@ mod test {
             [0, 0, 0, 0, 1, 0, 0]
         ]);
 
-        let maze = Maze::from_image(&img).unwrap();
-        let solution = Dijkstra::solve(&maze).unwrap();
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = Dijkstra.solve(&maze).unwrap();
         let path = create_path(&[(3, 1), (5, 1), (5, 3), (4, 3)], &maze);
         assert_eq!(path, solution.path)
     }
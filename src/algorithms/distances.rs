@@ -0,0 +1,253 @@
+use std::{cmp::Reverse, collections::BinaryHeap, io::Cursor};
+
+use image::{ImageBuffer, ImageError, ImageFormat, Luma, RgbImage};
+
+use crate::{
+    colors::Colormap,
+    img::{line, Image},
+    maze::Maze,
+    node::Point,
+};
+
+// Pixels that are walls, or can't be reached, in the field and the raw export.
+pub const UNREACHED: u32 = u32::MAX;
+
+// Distance from one node to every pixel of the corridors, walking through the maze.
+pub struct DistanceField {
+    pub width: u32,
+    pub height: u32,
+    distances: Vec<u32>,
+}
+
+impl DistanceField {
+    // Runs Dijkstra over the node graph, then fills in the corridors between the nodes. Every pixel
+    // of a corridor is reached through one of its two ends, whichever makes it closer.
+    pub fn new(maze: &Maze, from: Point) -> Self {
+        let mut distances = nodes(maze, from);
+        let index = |point: Point| (point.y * maze.width + point.x) as usize;
+        for node in maze.data.values() {
+            let start = distances[index(node.point)];
+            for next in node.children.values() {
+                let end = distances[index(*next)];
+                let pixels = route(maze, node.point, *next);
+                let length = pixels.len() as u32 - 1;
+                for (step, point) in (1..length).zip(&pixels[1..]) {
+                    let distance = start
+                        .saturating_add(step)
+                        .min(end.saturating_add(length - step));
+                    let pixel = &mut distances[index(*point)];
+                    *pixel = (*pixel).min(distance);
+                }
+            }
+        }
+        DistanceField {
+            width: maze.width,
+            height: maze.height,
+            distances,
+        }
+    }
+
+    pub fn at(&self, point: Point) -> Option<u32> {
+        if point.x >= self.width || point.y >= self.height {
+            return None;
+        }
+        Some(self.distances[(point.y * self.width + point.x) as usize]).filter(|d| *d != UNREACHED)
+    }
+
+    fn reached(&self) -> impl Iterator<Item = u32> + '_ {
+        self.distances.iter().copied().filter(|d| *d != UNREACHED)
+    }
+
+    // Distance to the farthest pixel that can be reached.
+    pub fn max(&self) -> u32 {
+        self.reached().max().unwrap_or(0)
+    }
+
+    pub fn reached_pixels(&self) -> usize {
+        self.reached().count()
+    }
+
+    // Grayscale with the distance as the value of each pixel, scaled down to fit when the maze is
+    // too long for 16 bits. Walls and pixels that can't be reached are white.
+    pub fn gray16(&self) -> ImageBuffer<Luma<u16>, Vec<u16>> {
+        let max = self.max() as u64;
+        let top = u16::MAX as u64 - 1;
+        ImageBuffer::from_fn(self.width, self.height, |x, y| {
+            let value = match self.at(Point::at(x, y)) {
+                None => u16::MAX,
+                Some(distance) if max <= top => distance as u16,
+                Some(distance) => (distance as u64 * top / max) as u16,
+            };
+            Luma([value])
+        })
+    }
+
+    // True when the grayscale export had to scale the distances down.
+    pub fn scaled(&self) -> bool {
+        self.max() >= u16::MAX as u32
+    }
+
+    // The entrance at the start of the colormap and the farthest pixel at its end. Pixels that can't
+    // be reached keep their color from the maze.
+    pub fn colored(&self, maze: &Image, colormap: Colormap) -> Image {
+        let max = self.max().max(1) as f32;
+        let image = RgbImage::from_fn(self.width, self.height, |x, y| {
+            match self.at(Point::at(x, y)) {
+                Some(distance) => colormap.sample(distance as f32 / max),
+                None => *maze.image.get_pixel(x, y),
+            }
+        });
+        Image { image }
+    }
+
+    // Every distance as a little endian u32, row by row, with UNREACHED for the rest.
+    pub fn raw(&self) -> Vec<u8> {
+        self.distances
+            .iter()
+            .flat_map(|distance| distance.to_le_bytes())
+            .collect()
+    }
+}
+
+// Encodes the grayscale export as a 16 bit png.
+pub fn encode_gray16(image: &ImageBuffer<Luma<u16>, Vec<u16>>) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = Cursor::new(Vec::new());
+    image.write_to(&mut bytes, ImageFormat::Png)?;
+    Ok(bytes.into_inner())
+}
+
+// Every pixel of the edge between two neighbouring nodes, around its corners.
+fn route(maze: &Maze, from: Point, to: Point) -> Vec<Point> {
+    let mut corners = vec![from];
+    corners.extend(maze.bends(from, to));
+    corners.push(to);
+    let mut pixels = vec![from];
+    for pair in corners.windows(2) {
+        pixels.extend(line(&pair[0], &pair[1]).into_iter().skip(1));
+    }
+    pixels
+}
+
+// Distance from a node to every node it connects to, over the corridors of the maze. Pixels that
+// aren't nodes, or can't be reached, stay at UNREACHED.
+pub(crate) fn nodes(maze: &Maze, from: Point) -> Vec<u32> {
+    let index = |point: Point| (point.y * maze.width + point.x) as usize;
+    let mut distances = vec![UNREACHED; (maze.width * maze.height) as usize];
+    distances[index(from)] = 0;
+    let mut queue = BinaryHeap::from([Reverse((0, from))]);
+    while let Some(Reverse((distance, point))) = queue.pop() {
+        if distance > distances[index(point)] {
+            continue;
+        }
+        let Some(node) = maze.node_at(point) else {
+            continue;
+        };
+        for next in node.children.values() {
+            let next_distance = distance + maze.distance(point, *next);
+            if next_distance < distances[index(*next)] {
+                distances[index(*next)] = next_distance;
+                queue.push(Reverse((next_distance, *next)));
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::{dijkstra::Dijkstra, Solver};
+    use crate::generator::{generate, GeneratorOptions};
+    use crate::node::NodeType;
+    use crate::statistics::Statistics;
+    use crate::test_utils::{maze_image, PATH};
+    use image::Pixel;
+    use pretty_assertions::assert_eq;
+    use std::collections::VecDeque;
+
+    // Distances over the pixels, one step at a time.
+    fn pixel_distances(image: &Image, from: Point) -> Vec<u32> {
+        let (width, height) = image.image.dimensions();
+        let open = |x: u32, y: u32| {
+            x < width && y < height && image.image.get_pixel(x, y).channels() == [255, 255, 255]
+        };
+        let mut distances = vec![UNREACHED; (width * height) as usize];
+        distances[(from.y * width + from.x) as usize] = 0;
+        let mut queue = VecDeque::from([from]);
+        while let Some(Point { x, y }) = queue.pop_front() {
+            let distance = distances[(y * width + x) as usize];
+            for (x, y) in [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ] {
+                if open(x, y) && distances[(y * width + x) as usize] == UNREACHED {
+                    distances[(y * width + x) as usize] = distance + 1;
+                    queue.push_back(Point::at(x, y));
+                }
+            }
+        }
+        distances
+    }
+
+    #[test]
+    fn every_pixel() {
+        let image = maze_image!([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 1, 0, 1, 0, 1, 0],
+            [0, 1, 0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let field = DistanceField::new(&maze, Point::at(3, 0));
+
+        assert_eq!(Some(10), field.at(Point::at(3, 6)));
+        assert_eq!(Some(7), field.at(Point::at(5, 1)));
+        assert_eq!(None, field.at(Point::at(0, 0)));
+        // Closed off from the rest of the maze
+        assert_eq!(None, field.at(Point::at(5, 5)));
+        assert_eq!(10, field.max());
+
+        let gray = field.gray16();
+        assert_eq!(Luma([10]), *gray.get_pixel(3, 6));
+        assert_eq!(Luma([u16::MAX]), *gray.get_pixel(5, 5));
+        let raw = field.raw();
+        assert_eq!(4 * 49, raw.len());
+        assert_eq!([10, 0, 0, 0], raw[4 * (6 * 7 + 3)..4 * (6 * 7 + 4)]);
+
+        let colored = field.colored(&image, Colormap::Viridis);
+        assert_eq!(
+            Colormap::Viridis.sample(0.0),
+            *colored.image.get_pixel(3, 0)
+        );
+        assert_eq!(PATH, *colored.image.get_pixel(5, 5));
+    }
+
+    #[test]
+    fn matches_pixel_distances() {
+        for seed in 0..20 {
+            let image = generate(&GeneratorOptions {
+                width: 2 + seed as u32 % 5,
+                height: 2 + seed as u32 % 4,
+                seed,
+                braid: 0.5,
+            });
+            let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+            let start = maze.data[&NodeType::Start].point;
+            let field = DistanceField::new(&maze, start);
+            assert_eq!(
+                pixel_distances(&image, start),
+                field.distances,
+                "maze {seed}"
+            );
+
+            let solution = Dijkstra.solve(&maze).unwrap();
+            let exit = maze.data[&NodeType::Exit].point;
+            assert_eq!(Some(solution.distance()), field.at(exit), "maze {seed}");
+        }
+    }
+}
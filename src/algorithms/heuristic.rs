@@ -0,0 +1,158 @@
+use std::str::FromStr;
+
+use crate::{algorithms::distances::nodes, maze::Maze, node::Point, utils::get_dist};
+
+// Landmarks picked for the landmark heuristic, each costs a distance for every pixel of the maze.
+const LANDMARKS: usize = 4;
+
+// Estimate of the distance left to the exit that guides A*.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    #[default]
+    Manhattan,
+    Euclidean,
+    Chebyshev,
+    // No estimate at all, which makes A* search like Dijkstra
+    Zero,
+    // Distances to a few landmarks, bounding the distance left by the triangle inequality
+    Landmarks,
+}
+
+impl FromStr for Heuristic {
+    type Err = &'static str;
+
+    fn from_str(heuristic: &str) -> Result<Self, Self::Err> {
+        match heuristic {
+            "manhattan" => Ok(Heuristic::Manhattan),
+            "euclidean" => Ok(Heuristic::Euclidean),
+            "chebyshev" => Ok(Heuristic::Chebyshev),
+            "zero" => Ok(Heuristic::Zero),
+            "landmarks" => Ok(Heuristic::Landmarks),
+            _ => Err("use manhattan, euclidean, chebyshev, zero or landmarks"),
+        }
+    }
+}
+
+// Factor the estimate is multiplied with. Above 1 the search expands fewer nodes, but the path it
+// finds can be up to that factor longer than the shortest one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weight(pub f64);
+
+impl Default for Weight {
+    fn default() -> Self {
+        Weight(1.0)
+    }
+}
+
+impl FromStr for Weight {
+    type Err = &'static str;
+
+    fn from_str(weight: &str) -> Result<Self, Self::Err> {
+        match weight.parse() {
+            Ok(weight) if weight >= 1.0 && f64::is_finite(weight) => Ok(Weight(weight)),
+            _ => Err("use a number of at least 1"),
+        }
+    }
+}
+
+// A heuristic set up for one maze, ready to estimate the distance from any node to the exit.
+pub(crate) struct Estimate {
+    heuristic: Heuristic,
+    weight: f64,
+    exit: Point,
+    width: u32,
+    // Distance from every landmark to every pixel, next to the distance to the exit
+    landmarks: Vec<(u32, Vec<u32>)>,
+}
+
+impl Estimate {
+    pub(crate) fn new(
+        heuristic: Heuristic,
+        weight: Weight,
+        maze: &Maze,
+        start: Point,
+        exit: Point,
+    ) -> Self {
+        let landmarks = match heuristic {
+            Heuristic::Landmarks => landmarks(maze, start, LANDMARKS)
+                .into_iter()
+                .map(|distances| (distances[index(maze.width, exit)], distances))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Estimate {
+            heuristic,
+            weight: weight.0,
+            exit,
+            width: maze.width,
+            landmarks,
+        }
+    }
+
+    pub(crate) fn distance(&self, point: &Point) -> u32 {
+        let (dx, dy) = (point.x.abs_diff(self.exit.x), point.y.abs_diff(self.exit.y));
+        let distance = match self.heuristic {
+            Heuristic::Manhattan => get_dist(point, &self.exit),
+            // Rounded down, the estimate must never be more than the real distance
+            Heuristic::Euclidean => (dx as f64).hypot(dy as f64) as u32,
+            Heuristic::Chebyshev => dx.max(dy),
+            Heuristic::Zero => 0,
+            Heuristic::Landmarks => {
+                let index = index(self.width, *point);
+                self.landmarks
+                    .iter()
+                    .filter(|(exit, distances)| *exit != u32::MAX && distances[index] != u32::MAX)
+                    .map(|(exit, distances)| exit.abs_diff(distances[index]))
+                    .max()
+                    .unwrap_or(0)
+            }
+        };
+        if self.weight == 1.0 {
+            distance
+        } else {
+            (distance as f64 * self.weight) as u32
+        }
+    }
+}
+
+fn index(width: u32, point: Point) -> usize {
+    (point.y * width + point.x) as usize
+}
+
+// Picks landmarks far apart, the start first and then every time the node farthest from the ones
+// picked so far, with the distance from each of them to every node.
+fn landmarks(maze: &Maze, start: Point, count: usize) -> Vec<Vec<u32>> {
+    let mut landmarks = vec![nodes(maze, start)];
+    let mut nearest = landmarks[0].clone();
+    while landmarks.len() < count {
+        let Some((farthest, _)) = nearest
+            .iter()
+            .enumerate()
+            .filter(|(_, distance)| **distance != u32::MAX)
+            .max_by_key(|(_, distance)| **distance)
+            .filter(|(_, distance)| **distance > 0)
+        else {
+            break;
+        };
+        let point = Point::at(farthest as u32 % maze.width, farthest as u32 / maze.width);
+        let distances = nodes(maze, point);
+        for (nearest, distance) in nearest.iter_mut().zip(&distances) {
+            *nearest = (*nearest).min(*distance);
+        }
+        landmarks.push(distances);
+    }
+    landmarks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parsed_options() {
+        assert_eq!(Ok(Heuristic::Chebyshev), "chebyshev".parse());
+        assert_eq!(Ok(Weight(1.5)), "1.5".parse());
+        assert_eq!(Err("use a number of at least 1"), "0.5".parse::<Weight>());
+        assert_eq!(Err("use a number of at least 1"), "inf".parse::<Weight>());
+    }
+}
@@ -1,95 +1,165 @@
 use std::collections::VecDeque;
+use std::str::FromStr;
 
-use crate::algorithms::{Solution, Solver};
+use crate::algorithms::{
+    steps::{Event, Search, Unsolvable},
+    Solution, Solver,
+};
 use crate::maze::Maze;
 use crate::node::NodeType::{Exit, Start};
-use crate::node::{Direction, NodeType, Point};
+use crate::node::{Direction, Node, Point};
 
 // Left turn algorithm that will find the shortest path by only constantly taking left turns.
-pub struct LeftTurn;
+#[derive(Debug, Default)]
+pub struct LeftTurn {
+    pub hand: Hand,
+}
 
-impl Solver for LeftTurn {
-    fn solve(maze: &Maze) -> Option<Solution> {
-        let maze = &maze.data;
-        if !maze.contains_key(&Start) {
-            return None;
+// The hand kept on the wall, a right hand takes right turns instead.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    #[default]
+    Left,
+    Right,
+}
+
+impl FromStr for Hand {
+    type Err = &'static str;
+
+    fn from_str(hand: &str) -> Result<Self, Self::Err> {
+        match hand {
+            "left" => Ok(Hand::Left),
+            "right" => Ok(Hand::Right),
+            _ => Err("use left or right"),
         }
+    }
+}
+
+impl Solver for LeftTurn {
+    fn search<'a>(&self, maze: &'a Maze) -> Box<dyn Search<'a> + 'a> {
+        let nodes = &maze.data;
+        let (Some(start), Some(end)) = (nodes.get(&Start), nodes.get(&Exit)) else {
+            return Box::new(Unsolvable);
+        };
 
-        let start = &maze[&Start];
-        let child = &start.children[&Direction::South];
-        let current = &maze.get(&NodeType::Path(Point::at(child.x, child.y)));
-        let mut current = if current.is_none() {
-            return None;
-        } else {
-            current.unwrap()
+        // Pruning a maze whose exit can't be reached leaves the entrance without a corridor
+        let Some(child) = start.children.get(&Direction::South) else {
+            return Box::new(Unsolvable);
+        };
+        // Straight to the exit in a simplified maze
+        let Some(current) = maze.node_at(*child) else {
+            return Box::new(Unsolvable);
         };
 
-        let mut path = VecDeque::from([start]);
-        let mut heading = Direction::South;
-        let turn = 1;
-        let start = &maze[&Start].point;
-        let end = &maze[&Exit].point;
-
-        let mut count = 1;
-
-        let completed = loop {
-            path.push_back(current);
-            count += 1;
-            let coords = &current.point;
-            if coords == start || coords == end {
-                if coords == end {
-                    break true;
-                }
-                break false;
-            }
-            let n = &current.children;
-
-            // if there is a node to the left add that as a path
-            if let Some(point) = n.get(&(heading - turn)) {
-                heading = heading - turn;
-                let node = maze
-                    .get(&NodeType::Path(*point))
-                    .unwrap_or(maze.get(&NodeType::Exit).unwrap());
-                current = node;
-                continue;
-            };
-
-            // if there is a node under us, use that as path
-            if let Some(point) = n.get(&heading) {
-                let node = maze
-                    .get(&NodeType::Path(*point))
-                    .unwrap_or(maze.get(&NodeType::Exit).unwrap());
-                current = node;
-                continue;
-            };
-
-            // if there is a node to the right, use that as path
-            if let Some(point) = n.get(&(heading + turn)) {
-                heading = heading + turn;
-                let node = maze
-                    .get(&NodeType::Path(*point))
-                    .unwrap_or(maze.get(&NodeType::Exit).unwrap());
-                current = node;
-                continue;
-            };
-
-            // last resort, turn back
-            if let Some(point) = n.get(&(heading + (turn * 2))) {
-                heading = heading + (turn * 2);
-                let node = maze
-                    .get(&NodeType::Path(*point))
-                    .unwrap_or(maze.get(&NodeType::Exit).unwrap());
-                current = node;
-                continue;
-            };
-
-            break false;
+        // The corridor from the entrance can turn corners in a simplified maze as well
+        let heading = match maze.bends(start.point, *child).last() {
+            Some(bend) => arrival(*bend, *child),
+            None => Direction::South,
         };
 
-        if !completed {
-            return None;
+        // Turning left three times is turning right
+        let turn = match self.hand {
+            Hand::Left => 1,
+            Hand::Right => 3,
+        };
+        Box::new(Walk {
+            maze,
+            current,
+            heading,
+            turn,
+            path: VecDeque::from([start]),
+            count: 1,
+            start: start.point,
+            end: end.point,
+            walking: true,
+            completed: false,
+        })
+    }
+}
+
+struct Walk<'a> {
+    maze: &'a Maze,
+    current: &'a Node,
+    heading: Direction,
+    turn: u32,
+    path: VecDeque<&'a Node>,
+    count: u32,
+    start: Point,
+    end: Point,
+    walking: bool,
+    completed: bool,
+}
+
+impl<'a> Search<'a> for Walk<'a> {
+    fn step(&mut self, events: &mut Vec<Event>) -> bool {
+        if !self.walking {
+            return false;
+        }
+        let current = self.current;
+        self.path.push_back(current);
+        self.count += 1;
+        let coords = current.point;
+        if coords == self.start || coords == self.end {
+            self.completed = coords == self.end;
+            if self.completed {
+                events.push(Event::Found(coords));
+            }
+            self.walking = false;
+            return false;
         }
+        events.push(Event::Expanded(coords));
+
+        let n = &current.children;
+        let turn = self.turn;
+        // Left if there is a node to the left, then straight on, then right and as a last resort
+        // turn back
+        let heading = [
+            self.heading - turn,
+            self.heading,
+            self.heading + turn,
+            self.heading + (turn * 2),
+        ]
+        .into_iter()
+        .find(|heading| n.contains_key(heading));
+
+        let Some(heading) = heading else {
+            self.walking = false;
+            return false;
+        };
+        let next = n[&heading];
+        // An edge of a simplified maze can turn corners, the walk goes on the way it arrives
+        self.heading = match self.maze.bends(coords, next).last() {
+            Some(bend) => arrival(*bend, next),
+            None => heading,
+        };
+        // Turning back to the entrance ends the walk on the next step, without a solution
+        let Some(node) = self.maze.node_at(next) else {
+            self.walking = false;
+            return false;
+        };
+        self.current = node;
+        true
+    }
+
+    fn decisions(&self) -> u32 {
+        self.count
+    }
+
+    fn solution(&self) -> Option<Solution<'a>> {
+        self.completed
+            .then(|| Solution::new(self.maze, self.count, self.path.clone()))
+    }
+}
 
-        Some(Solution::new(count, path))
+// Heading of a straight step from one point to the next.
+fn arrival(from: Point, to: Point) -> Direction {
+    if to.y < from.y {
+        Direction::North
+    } else if to.y > from.y {
+        Direction::South
+    } else if to.x < from.x {
+        Direction::West
+    } else {
+        Direction::East
     }
 }
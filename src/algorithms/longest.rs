@@ -0,0 +1,314 @@
+use std::collections::VecDeque;
+
+use image::{Pixel, Rgb};
+use serde::Serialize;
+
+use crate::{
+    img::Image,
+    maze::{PATHWAY, WALL},
+    node::Point,
+};
+
+// A route between two pixels of the maze, with every pixel it passes.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Route {
+    pub from: Point,
+    pub to: Point,
+    pub length: u32,
+    pub pixels: Vec<Point>,
+}
+
+// The corridors of a maze as a graph of pixels. In a perfect maze every two pixels are joined by
+// exactly one route, which makes the graph a tree and lets a few breadth first passes find the
+// farthest pixels.
+pub struct Corridors<'a> {
+    image: &'a Image,
+    width: u32,
+    height: u32,
+}
+
+// Distances from one pixel to every other pixel, and the pixel each one was reached from.
+struct Sweep {
+    from: Point,
+    distances: Vec<u32>,
+    previous: Vec<u32>,
+}
+
+impl<'a> Corridors<'a> {
+    pub fn new(image: &'a Image) -> Self {
+        let (width, height) = image.image.dimensions();
+        Corridors {
+            image,
+            width,
+            height,
+        }
+    }
+
+    fn open(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.image.image.get_pixel(x, y).channels() == PATHWAY
+    }
+
+    fn pixels(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| Point::at(x, y)))
+            .filter(|point| self.open(point.x, point.y))
+    }
+
+    // Pixels just inside a row of the border, where an opening in that row would lead.
+    fn inside(&self, row: u32) -> Vec<Point> {
+        (1..self.width.saturating_sub(1))
+            .map(|x| Point::at(x, row))
+            .filter(|point| self.open(point.x, point.y))
+            .collect()
+    }
+
+    fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        let Point { x, y } = point;
+        [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ]
+        .into_iter()
+        .filter(|(x, y)| self.open(*x, *y))
+        .map(|(x, y)| Point::at(x, y))
+    }
+
+    // True when the corridors are connected and have no loops.
+    pub fn is_perfect(&self) -> bool {
+        let mut pixels = 0;
+        let mut edges = 0;
+        for point in self.pixels() {
+            pixels += 1;
+            edges += self
+                .neighbours(point)
+                .filter(|next| next.x > point.x || next.y > point.y)
+                .count();
+        }
+        let Some(first) = self.pixels().next() else {
+            return false;
+        };
+        let reached = self
+            .sweep(first)
+            .distances
+            .iter()
+            .filter(|distance| **distance != u32::MAX)
+            .count();
+        reached == pixels && edges + 1 == pixels
+    }
+
+    fn sweep(&self, from: Point) -> Sweep {
+        let total = (self.width * self.height) as usize;
+        let index = |point: Point| (point.y * self.width + point.x) as usize;
+        let mut distances = vec![u32::MAX; total];
+        let mut previous = vec![u32::MAX; total];
+        distances[index(from)] = 0;
+        let mut queue = VecDeque::from([from]);
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[index(point)];
+            for next in self.neighbours(point) {
+                if distances[index(next)] == u32::MAX {
+                    distances[index(next)] = distance + 1;
+                    previous[index(next)] = index(point) as u32;
+                    queue.push_back(next);
+                }
+            }
+        }
+        Sweep {
+            from,
+            distances,
+            previous,
+        }
+    }
+
+    // The pixel of `candidates` farthest from where the sweep started.
+    fn farthest(&self, sweep: &Sweep, candidates: &[Point]) -> Option<(Point, u32)> {
+        candidates
+            .iter()
+            .map(|point| {
+                (
+                    *point,
+                    sweep.distances[(point.y * self.width + point.x) as usize],
+                )
+            })
+            .filter(|(_, distance)| *distance != u32::MAX)
+            .max_by_key(|(point, distance)| (*distance, *point))
+    }
+
+    fn route(&self, sweep: &Sweep, to: Point) -> Route {
+        let mut pixels = vec![to];
+        let mut index = (to.y * self.width + to.x) as usize;
+        while sweep.previous[index] != u32::MAX {
+            index = sweep.previous[index] as usize;
+            pixels.push(Point::at(
+                index as u32 % self.width,
+                index as u32 / self.width,
+            ));
+        }
+        pixels.reverse();
+        Route {
+            from: sweep.from,
+            to,
+            length: pixels.len() as u32 - 1,
+            pixels,
+        }
+    }
+
+    // The longest route between any two pixels, the diameter of the tree. The pixel farthest from
+    // any pixel is one end of it, and the pixel farthest from that end the other one.
+    pub fn diameter(&self) -> Option<Route> {
+        let pixels: Vec<Point> = self.pixels().collect();
+        let (end, _) = self.farthest(&self.sweep(*pixels.first()?), &pixels)?;
+        let sweep = self.sweep(end);
+        let (other, _) = self.farthest(&sweep, &pixels)?;
+        Some(self.route(&sweep, other))
+    }
+
+    // The longest route from an opening in the top row to an opening in the bottom row, which makes
+    // the hardest entrance and exit for the maze.
+    pub fn hardest_openings(&self) -> Option<Route> {
+        if self.height < 3 {
+            return None;
+        }
+        let (top, bottom) = (self.inside(1), self.inside(self.height - 2));
+
+        // In a tree the top pixel farthest from any pixel is an end of the longest route between two
+        // top pixels. Two passes find both ends, and the bottom pixel farthest from either of them
+        // is as far from the top as any
+        let (first, _) = self.farthest(&self.sweep(*top.first()?), &top)?;
+        let sweep = self.sweep(first);
+        let (second, _) = self.farthest(&sweep, &top)?;
+        let other = self.sweep(second);
+        let route = [sweep, other]
+            .iter()
+            .filter_map(|sweep| {
+                let (end, distance) = self.farthest(sweep, &bottom)?;
+                Some((distance, self.route(sweep, end)))
+            })
+            .max_by_key(|(distance, _)| *distance)
+            .map(|(_, route)| route)?;
+
+        // Through the openings in the border
+        let entrance = Point::at(route.from.x, 0);
+        let exit = Point::at(route.to.x, self.height - 1);
+        let mut pixels = vec![entrance];
+        pixels.extend(route.pixels);
+        pixels.push(exit);
+        Some(Route {
+            from: entrance,
+            to: exit,
+            length: route.length + 2,
+            pixels,
+        })
+    }
+
+    // The route between the current entrance and exit.
+    pub fn current(&self) -> Option<Route> {
+        let opening = |y| (0..self.width).find(|x| self.open(*x, y));
+        let entrance = Point::at(opening(0)?, 0);
+        let exit = Point::at(opening(self.height - 1)?, self.height - 1);
+        let sweep = self.sweep(entrance);
+        self.farthest(&sweep, &[exit])?;
+        Some(self.route(&sweep, exit))
+    }
+}
+
+// Copy of the maze with the entrance and exit moved to the ends of the route.
+pub fn with_openings(image: &Image, route: &Route) -> Image {
+    let mut image = Image {
+        image: image.image.clone(),
+    };
+    let (width, height) = image.image.dimensions();
+    for x in 0..width {
+        for y in [0, height - 1] {
+            image.image.put_pixel(x, y, Rgb(WALL));
+        }
+    }
+    for opening in [route.from, route.to] {
+        image.image.put_pixel(opening.x, opening.y, Rgb(PATHWAY));
+    }
+    image
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::{generate, GeneratorOptions};
+    use crate::test_utils::maze_image;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn longest_routes() {
+        let image = maze_image!([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 1, 0, 1, 0, 1, 0],
+            [0, 1, 0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let corridors = Corridors::new(&image);
+        assert!(corridors.is_perfect());
+
+        assert_eq!(10, corridors.current().unwrap().length);
+
+        // From either dead end on the right to the exit
+        let longest = corridors.diameter().unwrap();
+        assert_eq!(15, longest.length);
+        assert!([longest.from, longest.to].contains(&Point::at(3, 6)));
+
+        let hardest = corridors.hardest_openings().unwrap();
+        assert_eq!(
+            (Point::at(5, 0), Point::at(3, 6), 16),
+            (hardest.from, hardest.to, hardest.length)
+        );
+
+        let rewritten = with_openings(&image, &hardest);
+        let corridors = Corridors::new(&rewritten);
+        assert_eq!(Some(hardest), corridors.current());
+    }
+
+    #[test]
+    fn matches_every_pair() {
+        for seed in 0..20 {
+            let image = generate(&GeneratorOptions {
+                width: 2 + seed as u32 % 5,
+                height: 2 + seed as u32 % 4,
+                seed,
+                braid: 0.0,
+            });
+            let corridors = Corridors::new(&image);
+            assert!(corridors.is_perfect(), "maze {seed}");
+
+            let (top, bottom) = (
+                corridors.inside(1),
+                corridors.inside(image.image.height() - 2),
+            );
+            let brute_force = top
+                .iter()
+                .map(|from| {
+                    let sweep = corridors.sweep(*from);
+                    corridors.farthest(&sweep, &bottom).unwrap().1
+                })
+                .max()
+                .unwrap();
+            let hardest = corridors.hardest_openings().unwrap();
+            assert_eq!(brute_force + 2, hardest.length, "maze {seed}");
+
+            let pixels: Vec<Point> = corridors.pixels().collect();
+            let brute_force = pixels
+                .iter()
+                .map(|from| {
+                    corridors
+                        .farthest(&corridors.sweep(*from), &pixels)
+                        .unwrap()
+                        .1
+                })
+                .max();
+            assert_eq!(brute_force, corridors.diameter().map(|route| route.length));
+        }
+    }
+}
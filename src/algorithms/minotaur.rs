@@ -0,0 +1,244 @@
+use std::collections::VecDeque;
+
+use fxhash::FxHashMap;
+use image::Pixel;
+
+use crate::{
+    algorithms::{Aborted, SolveContext},
+    img::Image,
+    maze::PATHWAY,
+    node::Point,
+};
+
+// Theseus and the Minotaur, where they stand at the start of a turn.
+type State = (Point, Point);
+
+// A way out for Theseus, with where both of them went on the way.
+#[derive(Debug, PartialEq)]
+pub struct Escape {
+    // Where Theseus stands after every turn, from the entrance to the exit
+    pub theseus: Vec<Point>,
+    // Every pixel the Minotaur walks, from where it started
+    pub minotaur: Vec<Point>,
+    pub turns: u32,
+    // Turns Theseus stood still to let the Minotaur get stuck
+    pub waits: u32,
+    // Positions of the two looked at before finding the way out
+    pub states: u32,
+}
+
+// The game of the classic puzzle, played on the pixels of a maze. Every turn Theseus takes one step
+// or waits, then the Minotaur takes `steps` steps towards him. A step goes across if that gets it
+// closer and the way is open, else up or down on the same terms, else the Minotaur stays put.
+pub struct Pursuit<'a> {
+    image: &'a Image,
+    steps: u32,
+}
+
+impl<'a> Pursuit<'a> {
+    pub fn new(image: &'a Image, steps: u32) -> Self {
+        Pursuit { image, steps }
+    }
+
+    pub fn open(&self, point: Point) -> bool {
+        let (width, height) = self.image.image.dimensions();
+        point.x < width
+            && point.y < height
+            && self.image.image.get_pixel(point.x, point.y).channels() == PATHWAY
+    }
+
+    fn opening(&self, y: u32) -> Option<Point> {
+        (0..self.image.image.width())
+            .map(|x| Point::at(x, y))
+            .find(|point| self.open(*point))
+    }
+
+    // One step of the Minotaur towards Theseus.
+    fn chase(&self, minotaur: Point, theseus: Point) -> Point {
+        let towards = |from: u32, to: u32| match from.cmp(&to) {
+            std::cmp::Ordering::Less => Some(from + 1),
+            std::cmp::Ordering::Greater => Some(from - 1),
+            std::cmp::Ordering::Equal => None,
+        };
+        towards(minotaur.x, theseus.x)
+            .map(|x| Point::at(x, minotaur.y))
+            .filter(|point| self.open(*point))
+            .or_else(|| {
+                towards(minotaur.y, theseus.y)
+                    .map(|y| Point::at(minotaur.x, y))
+                    .filter(|point| self.open(*point))
+            })
+            .unwrap_or(minotaur)
+    }
+
+    // Every step the Minotaur takes in a turn, none once it has caught Theseus.
+    fn turn(&self, minotaur: Point, theseus: Point) -> Option<Vec<Point>> {
+        let mut walked = Vec::with_capacity(self.steps as usize);
+        let mut minotaur = minotaur;
+        for _ in 0..self.steps {
+            minotaur = self.chase(minotaur, theseus);
+            if minotaur == theseus {
+                return None;
+            }
+            walked.push(minotaur);
+        }
+        Some(walked)
+    }
+
+    fn moves(&self, theseus: Point) -> impl Iterator<Item = Point> + '_ {
+        let Point { x, y } = theseus;
+        [
+            theseus,
+            Point::at(x, y.wrapping_sub(1)),
+            Point::at(x + 1, y),
+            Point::at(x, y + 1),
+            Point::at(x.wrapping_sub(1), y),
+        ]
+        .into_iter()
+        .filter(|point| self.open(*point))
+    }
+
+    // Searches every position of the two breadth first for the fewest turns to the exit, Theseus
+    // escapes as soon as he reaches it.
+    pub fn escape(
+        &self,
+        minotaur: Point,
+        context: &SolveContext,
+    ) -> Result<Option<Escape>, Aborted> {
+        let (Some(entrance), Some(exit)) =
+            (self.opening(0), self.opening(self.image.image.height() - 1))
+        else {
+            return Ok(None);
+        };
+        if minotaur == entrance {
+            return Ok(None);
+        }
+
+        let start = (entrance, minotaur);
+        let mut previous: FxHashMap<State, State> = FxHashMap::default();
+        let mut queue = VecDeque::from([start]);
+        let mut states = 0;
+        while let Some((theseus, minotaur)) = queue.pop_front() {
+            states += 1;
+            context.check(states)?;
+            for next in self.moves(theseus) {
+                if next == minotaur {
+                    continue;
+                }
+                if next == exit {
+                    let mut path = vec![(next, minotaur), (theseus, minotaur)];
+                    while let Some(state) = previous.get(&path[path.len() - 1]) {
+                        path.push(*state);
+                    }
+                    path.reverse();
+                    return Ok(Some(self.replay(&path, states)));
+                }
+                let Some(walked) = self.turn(minotaur, next) else {
+                    continue;
+                };
+                let state = (next, walked[walked.len() - 1]);
+                if state != start && !previous.contains_key(&state) {
+                    previous.insert(state, (theseus, minotaur));
+                    queue.push_back(state);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // Plays the turns again to fill in the steps of the Minotaur between them.
+    fn replay(&self, path: &[State], states: u32) -> Escape {
+        let theseus: Vec<Point> = path.iter().map(|(theseus, _)| *theseus).collect();
+        let mut minotaur = vec![path[0].1];
+        for (theseus, _) in &path[1..path.len() - 1] {
+            let last = minotaur[minotaur.len() - 1];
+            minotaur.extend(self.turn(last, *theseus).unwrap_or_default());
+        }
+        minotaur.dedup();
+        Escape {
+            turns: theseus.len() as u32 - 1,
+            waits: theseus.windows(2).filter(|pair| pair[0] == pair[1]).count() as u32,
+            theseus,
+            minotaur,
+            states,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::{generate, GeneratorOptions};
+    use crate::test_utils::maze_image;
+    use crate::utils::get_dist;
+
+    use pretty_assertions::assert_eq;
+
+    // Plays the escape again, checking that nobody walks through walls and Theseus is never caught.
+    fn check(pursuit: &Pursuit, escape: &Escape) {
+        let theseus = &escape.theseus;
+        assert!(theseus.iter().all(|point| pursuit.open(*point)));
+        assert!(theseus
+            .windows(2)
+            .all(|pair| get_dist(&pair[0], &pair[1]) <= 1));
+        assert_eq!(Some(&theseus[0]), pursuit.opening(0).as_ref());
+
+        let mut minotaur = vec![escape.minotaur[0]];
+        for next in &theseus[1..theseus.len() - 1] {
+            let walked = pursuit.turn(minotaur[minotaur.len() - 1], *next);
+            minotaur.extend(walked.expect("Theseus was caught"));
+        }
+        minotaur.dedup();
+        assert_eq!(escape.minotaur, minotaur);
+    }
+
+    #[test]
+    fn waits_for_the_minotaur() {
+        let image = maze_image!([
+            [0, 0, 0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 0, 0, 1, 0],
+            [0, 1, 0, 1, 1, 1, 0, 1, 0],
+            [0, 1, 0, 1, 0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 0, 1, 1, 1, 0],
+            [0, 0, 0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let pursuit = Pursuit::new(&image, 1);
+        let context = SolveContext::default();
+
+        // The shortest way out is 12 steps, but the Minotaur would cut it off. Theseus has to lure
+        // it up the left side and wait for it first
+        let escape = pursuit.escape(Point::at(1, 5), &context).unwrap().unwrap();
+        assert_eq!((17, 1), (escape.turns, escape.waits));
+        assert_eq!(Some(&Point::at(5, 6)), escape.theseus.last());
+        check(&pursuit, &escape);
+
+        // Right next to the entrance
+        assert_eq!(None, pursuit.escape(Point::at(2, 1), &context).unwrap());
+        // Twice as fast it can't be shaken off
+        let pursuit = Pursuit::new(&image, 2);
+        assert_eq!(None, pursuit.escape(Point::at(1, 5), &context).unwrap());
+    }
+
+    #[test]
+    fn escapes_are_safe() {
+        let mut escapes = 0;
+        for seed in 0..20 {
+            let image = generate(&GeneratorOptions {
+                width: 3 + seed as u32 % 5,
+                height: 3 + seed as u32 % 4,
+                seed,
+                braid: 0.5,
+            });
+            let pursuit = Pursuit::new(&image, 2);
+            let (width, height) = image.image.dimensions();
+            // Cells of generated mazes are at odd pixels
+            let minotaur = Point::at((width / 2) | 1, (height / 2) | 1);
+            if let Some(escape) = pursuit.escape(minotaur, &SolveContext::default()).unwrap() {
+                check(&pursuit, &escape);
+                escapes += 1;
+            }
+        }
+        assert!(escapes > 0);
+    }
+}
@@ -1,42 +1,297 @@
 use std::collections::VecDeque;
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::maze::Maze;
-use crate::node::Node;
+use serde::{Serialize, Serializer};
+
+use crate::img::line;
+use crate::maze::{Edges, Maze};
+use crate::node::{Node, Point};
+use crate::progress::{Progress, Stage};
+use crate::utils::get_dist;
 
 pub mod a_star;
 pub mod breadth_first;
 pub mod depth_first;
 pub mod dijkstra;
+pub mod distances;
+pub mod heuristic;
 pub mod left_turn;
+pub mod longest;
+pub mod minotaur;
+pub mod paths;
+pub mod registry;
+pub mod steps;
+
+use registry::{Options, Registration};
+use steps::{Event, Search, Steps};
+
+// A solver picked by name with its options, like `a-star` or `left-turn:hand=right`.
+#[derive(Clone)]
+pub struct Algorithm {
+    name: String,
+    registration: &'static Registration,
+    solver: Arc<dyn Solver>,
+}
+
+impl Algorithm {
+    pub fn new(registration: &'static Registration, options: &Options) -> Result<Self, String> {
+        let solver = registration.build(options)?;
+        let name = if options.is_empty() {
+            registration.name.to_string()
+        } else {
+            format!("{}:{options}", registration.name)
+        };
+        Ok(Algorithm {
+            name,
+            registration,
+            solver: Arc::from(solver),
+        })
+    }
+
+    // Every registered solver with its default options.
+    pub fn all() -> Vec<Algorithm> {
+        registry::SOLVERS
+            .iter()
+            .map(|registration| Algorithm::new(registration, &Options::default()).unwrap())
+            .collect()
+    }
+
+    // Name of the algorithm with its options, as written on the command line.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Solves the maze, a path that doesn't lead from the entrance to the exit counts as no solution.
+    pub fn solve<'a>(&self, maze: &'a Maze) -> Option<Solution<'a>> {
+        self.solve_within(maze, &SolveContext::default())
+            .ok()
+            .flatten()
+    }
+
+    // Solves the maze within the bounds of the context.
+    pub fn solve_within<'a>(
+        &self,
+        maze: &'a Maze,
+        context: &SolveContext,
+    ) -> Result<Option<Solution<'a>>, Aborted> {
+        if let Some(progress) = &context.progress {
+            progress.start(Stage::Solve, maze.data.len() as u64);
+        }
+        let solution = self.solver.solve_within(maze, context);
+        if let Some(progress) = &context.progress {
+            progress.finish(Stage::Solve);
+        }
+        // Searches only give a solution once they reach the exit
+        debug_assert!(!matches!(&solution, Ok(Some(solution)) if !solution.is_complete()));
+        solution
+    }
+
+    // Solves the maze like `solve_within`, handing every event of the search to `watch`.
+    pub fn solve_watched<'a>(
+        &self,
+        maze: &'a Maze,
+        context: &SolveContext,
+        mut watch: impl FnMut(Event),
+    ) -> Result<Option<Solution<'a>>, Aborted> {
+        if let Some(progress) = &context.progress {
+            progress.start(Stage::Solve, maze.data.len() as u64);
+        }
+        let mut steps = self.steps(maze);
+        let solution = loop {
+            let Some(event) = steps.next() else {
+                break Ok(steps.solution());
+            };
+            watch(event);
+            if let Err(aborted) = context.check(steps.decisions()) {
+                break Err(aborted);
+            }
+        };
+        if let Some(progress) = &context.progress {
+            progress.finish(Stage::Solve);
+        }
+        solution
+    }
+
+    pub fn steps<'a>(&self, maze: &'a Maze) -> Steps<'a> {
+        self.solver.steps(maze)
+    }
+}
 
-#[derive(Debug, Default)]
-pub enum Algorithm {
-    LeftTurn,
-    Dijkstra,
-    AStar,
-    BreadthFirst,
-    DepthFirst,
-    #[default]
-    None,
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(algorithm: &str) -> Result<Self, Self::Err> {
+        let (name, options) = match algorithm.split_once(':') {
+            Some((name, options)) => (name, options.parse()?),
+            None => (algorithm, Options::default()),
+        };
+        let registration = registry::find(name).ok_or_else(|| {
+            let names: Vec<&str> = registry::SOLVERS.iter().map(|r| r.name).collect();
+            format!(
+                "unknown algorithm `{name}`, use one of {}",
+                names.join(", ")
+            )
+        })?;
+        Algorithm::new(registration, &options)
+    }
+}
+
+impl PartialEq for Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Debug for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
 }
 
 impl Display for Algorithm {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let algorithm = match self {
-            Algorithm::LeftTurn => "Left Turn ◀️◀️◀️",
-            Algorithm::Dijkstra => "Dijkstra 👴",
-            Algorithm::AStar => "A🌟",
-            Algorithm::BreadthFirst => "Breadth First 🍞",
-            Algorithm::DepthFirst => "Depth First",
-            _ => unimplemented!(),
-        };
-        write!(f, "{}", algorithm)
+        match self.name.split_once(':') {
+            Some((_, options)) => write!(f, "{} ({options})", self.registration.label),
+            None => write!(f, "{}", self.registration.label),
+        }
+    }
+}
+
+// Object safe, so solvers can carry their options and be picked at runtime from the registry.
+pub trait Solver: Send + Sync {
+    // Starts a search of the maze, which runs one decision at a time.
+    fn search<'a>(&self, maze: &'a Maze) -> Box<dyn Search<'a> + 'a>;
+
+    // Solves the maze, giving up with `Aborted` as soon as the context says so.
+    fn solve_within<'a>(
+        &self,
+        maze: &'a Maze,
+        context: &SolveContext,
+    ) -> Result<Option<Solution<'a>>, Aborted> {
+        let mut search = self.search(maze);
+        let mut events = Vec::new();
+        let mut expanded = 0;
+        loop {
+            let running = search.step(&mut events);
+            expanded += expansions(&events);
+            events.clear();
+            if !running {
+                break;
+            }
+            context.check(search.decisions())?;
+        }
+        Ok(search
+            .solution()
+            .map(|solution| solution.expanded(expanded)))
+    }
+
+    fn solve<'a>(&self, maze: &'a Maze) -> Option<Solution<'a>> {
+        self.solve_within(maze, &SolveContext::default())
+            .ok()
+            .flatten()
+    }
+
+    // Every event of a search, one at a time.
+    fn steps<'a>(&self, maze: &'a Maze) -> Steps<'a> {
+        Steps::new(self.search(maze))
+    }
+}
+
+// Nodes a search expanded in the events of a decision.
+pub(crate) fn expansions(events: &[Event]) -> u32 {
+    events
+        .iter()
+        .filter(|event| matches!(event, Event::Expanded(_)))
+        .count() as u32
+}
+
+// The clock, the cancel flag and the progress are only looked at every so many decisions, as that is
+// slow compared to a decision.
+const CHECK_INTERVAL: u32 = 256;
+
+// Bounds on a single run of a solver and where it reports its progress. The default context never
+// gives up.
+#[derive(Clone, Default)]
+pub struct SolveContext {
+    pub deadline: Option<Instant>,
+    pub max_steps: Option<u32>,
+    pub cancel: Arc<AtomicBool>,
+    pub progress: Option<Arc<dyn Progress>>,
+}
+
+impl SolveContext {
+    pub fn new(timeout: Option<Duration>, max_steps: Option<u32>) -> Self {
+        SolveContext {
+            // A timeout too far in the future to represent is no deadline at all
+            deadline: timeout.and_then(|timeout| Instant::now().checked_add(timeout)),
+            max_steps,
+            cancel: Arc::default(),
+            progress: None,
+        }
+    }
+
+    // Called by the solvers with the number of decisions made so far.
+    pub fn check(&self, decisions: u32) -> Result<(), Aborted> {
+        if let Some(max) = self.max_steps.filter(|max| decisions > *max) {
+            return Err(Aborted {
+                reason: AbortReason::MaxSteps,
+                decisions: max,
+            });
+        }
+        let abort = |reason| Err(Aborted { reason, decisions });
+        if !decisions.is_multiple_of(CHECK_INTERVAL) {
+            return Ok(());
+        }
+        if let Some(progress) = &self.progress {
+            progress.advance(Stage::Solve, decisions as u64);
+        }
+        if self.cancel.load(Ordering::Relaxed) {
+            return abort(AbortReason::Cancelled);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return abort(AbortReason::Timeout);
+        }
+        Ok(())
     }
 }
 
-pub trait Solver {
-    fn solve(maze: &Maze) -> Option<Solution>;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AbortReason {
+    Timeout,
+    MaxSteps,
+    Cancelled,
+}
+
+// A solver that gave up, with the decisions it made until then.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Aborted {
+    pub reason: AbortReason,
+    pub decisions: u32,
+}
+
+impl Display for Aborted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.reason {
+            AbortReason::Timeout => "ran out of time",
+            AbortReason::MaxSteps => "ran out of steps",
+            AbortReason::Cancelled => "was cancelled",
+        };
+        write!(f, "{reason} after {} decisions", self.decisions)
+    }
 }
 
 #[derive(Debug)]
@@ -44,15 +299,239 @@ pub struct Solution<'a> {
     pub count: u32,
     pub path: VecDeque<&'a Node>,
     pub length: usize,
+    // Nodes the search expanded, filled in by whatever ran it
+    pub expanded: u32,
+    // Corners of the edges between the nodes, when the maze was simplified
+    edges: &'a Edges,
 }
 
 impl<'a> Solution<'a> {
-    pub(crate) fn new(count: u32, path: VecDeque<&'a Node>) -> Self {
+    pub(crate) fn new(maze: &'a Maze, count: u32, path: VecDeque<&'a Node>) -> Self {
         let length = path.len();
         Self {
             count,
             path,
             length,
+            expanded: 0,
+            edges: &maze.edges,
+        }
+    }
+
+    pub(crate) fn expanded(self, expanded: u32) -> Self {
+        Self { expanded, ..self }
+    }
+
+    // True when the path runs between the entrance and the exit over edges of the maze.
+    pub fn is_complete(&self) -> bool {
+        let (Some(first), Some(last)) = (self.path.front(), self.path.back()) else {
+            return false;
+        };
+        let ends = (first.start && last.end) || (first.end && last.start);
+        ends && self
+            .path
+            .iter()
+            .zip(self.path.iter().skip(1))
+            .all(|(a, b)| {
+                a.children.values().any(|point| *point == b.point)
+                    || b.children.values().any(|point| *point == a.point)
+            })
+    }
+
+    // The points of the solution nodes, ordered from the entrance to the exit. The corners of
+    // simplified edges are put back in, so every two points are joined by a straight line.
+    pub fn points(&self) -> Vec<Point> {
+        let mut points = Vec::with_capacity(self.path.len());
+        for node in &self.path {
+            if let Some(previous) = points.last() {
+                if let Some(edge) = self.edges.get(&(*previous, node.point)) {
+                    points.extend(&edge.bends);
+                }
+            }
+            points.push(node.point);
+        }
+        if self.path.front().is_some_and(|node| !node.start) {
+            points.reverse();
+        }
+        points
+    }
+
+    // Number of pixels walked from the entrance to the exit.
+    pub fn distance(&self) -> u32 {
+        let points = self.points();
+        points
+            .windows(2)
+            .map(|pair| get_dist(&pair[0], &pair[1]))
+            .sum()
+    }
+
+    // Expands the solution into every pixel it passes, ordered from the entrance to the exit.
+    pub fn pixels(&self) -> Vec<Point> {
+        let points = self.points();
+        let mut pixels = Vec::new();
+        if let Some(first) = points.first() {
+            pixels.push(*first);
+        }
+        for pair in points.windows(2) {
+            pixels.extend(line(&pair[0], &pair[1]).into_iter().skip(1));
+        }
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::{generate, GeneratorOptions, Rng};
+    use crate::img::Image;
+    use crate::maze::{PATHWAY, WALL};
+    use crate::node::NodeType;
+    use crate::statistics::Statistics;
+    use image::{Pixel, Rgb, RgbImage};
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    // Walls around random pixels, with the entrance and the exit in the top and bottom row.
+    fn random_image(rng: &mut Rng) -> Image {
+        let (width, height) = (3 + rng.below(12), 3 + rng.below(12));
+        let mut image = RgbImage::from_pixel(width, height, Rgb(WALL));
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                if rng.chance(0.7) {
+                    image.put_pixel(x, y, Rgb(PATHWAY));
+                }
+            }
+        }
+        let (entrance, exit) = (1 + rng.below(width - 2), 1 + rng.below(width - 2));
+        for (x, y) in [
+            (entrance, 0),
+            (entrance, 1),
+            (exit, height - 2),
+            (exit, height - 1),
+        ] {
+            image.put_pixel(x, y, Rgb(PATHWAY));
+        }
+        Image { image }
+    }
+
+    // Length of the shortest route over the pixels, where every step costs the same.
+    fn pixel_distance(image: &Image) -> Option<u32> {
+        let image = &image.image;
+        let (width, height) = image.dimensions();
+        let open = |x: u32, y: u32| image.get_pixel(x, y).channels() == PATHWAY;
+        let entrance = (0..width).find(|x| open(*x, 0))?;
+        let mut distances = vec![u32::MAX; (width * height) as usize];
+        distances[entrance as usize] = 0;
+        let mut queue = VecDeque::from([(entrance, 0)]);
+        while let Some((x, y)) = queue.pop_front() {
+            let distance = distances[(y * width + x) as usize];
+            if y == height - 1 && y > 0 {
+                return Some(distance);
+            }
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (x, y) in neighbours {
+                if x < width && y < height && open(x, y) {
+                    let index = (y * width + x) as usize;
+                    if distances[index] == u32::MAX {
+                        distances[index] = distance + 1;
+                        queue.push_back((x, y));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Distance to the exit over the node graph, relaxing every edge until nothing changes.
+    fn brute_force_distance(maze: &Maze) -> Option<u32> {
+        let start = maze.data.get(&NodeType::Start)?;
+        let exit = maze.data.get(&NodeType::Exit)?;
+        let mut distances = HashMap::from([(start.point, 0)]);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in maze.data.values() {
+                let Some(&distance) = distances.get(&node.point) else {
+                    continue;
+                };
+                for next in node.children.values() {
+                    let new_distance = distance + get_dist(&node.point, next);
+                    if distances
+                        .get(next)
+                        .is_none_or(|current| new_distance < *current)
+                    {
+                        distances.insert(*next, new_distance);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        distances.get(&exit.point).copied()
+    }
+
+    fn check_shortest(image: &Image, case: &str) {
+        let maze = Maze::from_image(image, &mut Statistics::default()).unwrap();
+        let expected = brute_force_distance(&maze);
+        assert_eq!(pixel_distance(image), expected, "{case}");
+        let names = [
+            "dijkstra",
+            "a-star",
+            "a-star:heuristic=euclidean",
+            "a-star:heuristic=chebyshev",
+            "a-star:heuristic=zero",
+            "a-star:heuristic=landmarks",
+        ];
+        for name in names {
+            let algorithm: Algorithm = name.parse().unwrap();
+            let solution = algorithm.solve(&maze);
+            assert_eq!(
+                expected,
+                solution.as_ref().map(Solution::distance),
+                "{name} on {case}"
+            );
+            if let Some(solution) = solution {
+                assert_eq!(
+                    expected.map(|distance| distance as usize + 1),
+                    Some(solution.pixels().len()),
+                    "{name} on {case}"
+                );
+            }
+        }
+
+        // Weighted A* trades the shortest path for speed, but stays within its weight
+        let algorithm: Algorithm = "a-star:weight=1.5".parse().unwrap();
+        let solution = algorithm.solve(&maze);
+        assert_eq!(expected.is_some(), solution.is_some(), "weighted on {case}");
+        if let (Some(expected), Some(solution)) = (expected, solution) {
+            assert!(
+                solution.distance() as f64 <= expected as f64 * 1.5,
+                "weighted on {case}"
+            );
+        }
+    }
+
+    #[test]
+    fn shortest_paths_on_generated_mazes() {
+        for seed in 0..100 {
+            let image = generate(&GeneratorOptions {
+                width: 2 + seed as u32 % 13,
+                height: 2 + seed as u32 % 7,
+                seed,
+                braid: (seed % 5) as f32 / 4.0,
+            });
+            check_shortest(&image, &format!("maze {seed}"));
+        }
+    }
+
+    #[test]
+    fn shortest_paths_on_random_images() {
+        let mut rng = Rng::new(7);
+        for case in 0..300 {
+            check_shortest(&random_image(&mut rng), &format!("image {case}"));
         }
     }
 }
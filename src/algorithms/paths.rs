@@ -0,0 +1,302 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+    algorithms::{Aborted, Solution, SolveContext},
+    maze::Maze,
+    node::{NodeType, Point},
+    utils::get_dist,
+};
+
+// Finds up to `k` paths from the entrance to the exit without loops, shortest first, with Yen's
+// algorithm. Every next path is the shortest one that leaves one of the paths found so far at some
+// node and doesn't rejoin it.
+pub fn k_shortest<'a>(
+    maze: &'a Maze,
+    k: usize,
+    context: &SolveContext,
+) -> Result<Vec<Solution<'a>>, Aborted> {
+    let (Some(start), Some(exit)) = (
+        maze.data.get(&NodeType::Start),
+        maze.data.get(&NodeType::Exit),
+    ) else {
+        return Ok(Vec::new());
+    };
+    let mut search = Search {
+        maze,
+        context,
+        decisions: 0,
+    };
+
+    let mut found: Vec<(u32, Vec<Point>)> = Vec::new();
+    if let Some(first) = search.shortest(start.point, exit.point, &[], &FxHashSet::default())? {
+        found.push(first);
+    }
+    let mut candidates = BinaryHeap::new();
+    let mut seen = FxHashSet::default();
+
+    while found.len() < k {
+        let Some((_, previous)) = found.last() else {
+            break;
+        };
+        let previous = previous.clone();
+        for spur in 0..previous.len() - 1 {
+            let root = &previous[..=spur];
+            // Edges the paths sharing this root already leave it by
+            let removed_edges: Vec<(Point, Point)> = found
+                .iter()
+                .filter(|(_, path)| path.len() > spur + 1 && path[..=spur] == *root)
+                .map(|(_, path)| (path[spur], path[spur + 1]))
+                .collect();
+            // The root can't be walked through again, that would make a loop
+            let removed_nodes: FxHashSet<Point> = root[..spur].iter().copied().collect();
+
+            let Some((spur_distance, spur_path)) =
+                search.shortest(root[spur], exit.point, &removed_edges, &removed_nodes)?
+            else {
+                continue;
+            };
+            let mut path = root[..spur].to_vec();
+            path.extend(spur_path);
+            if seen.insert(path.clone()) {
+                let distance = distance(maze, root) + spur_distance;
+                candidates.push(Reverse((distance, path)));
+            }
+        }
+        let Some(Reverse(next)) = candidates.pop() else {
+            break;
+        };
+        found.push(next);
+    }
+
+    Ok(found
+        .into_iter()
+        .map(|(_, path)| solution(maze, &path, search.decisions))
+        .collect())
+}
+
+// Finds every path from the entrance to the exit without loops that is at most `max_length` pixels
+// long, shortest first. The search stops once it has found `limit` of them, which are then the
+// first ones it came across rather than the shortest.
+pub fn all_simple<'a>(
+    maze: &'a Maze,
+    max_length: u32,
+    limit: usize,
+    context: &SolveContext,
+) -> Result<Vec<Solution<'a>>, Aborted> {
+    let (Some(start), Some(exit)) = (
+        maze.data.get(&NodeType::Start),
+        maze.data.get(&NodeType::Exit),
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    let mut found: Vec<(u32, Vec<Point>)> = Vec::new();
+    let mut decisions = 0;
+    let mut path = vec![start.point];
+    let mut on_path = FxHashSet::from_iter([start.point]);
+    // Every node on the path with the children still to try and the distance to it
+    let mut stack = vec![(children(maze, start.point), 0)];
+    while let Some((remaining, distance)) = stack.last_mut() {
+        let distance = *distance;
+        let Some(next) = remaining.pop() else {
+            stack.pop();
+            on_path.remove(&path.pop().unwrap());
+            continue;
+        };
+        decisions += 1;
+        context.check(decisions)?;
+
+        let current = path[path.len() - 1];
+        let next_distance = distance + maze.distance(current, next);
+        // Even a straight line to the exit would make the path too long
+        if on_path.contains(&next)
+            || next_distance.saturating_add(get_dist(&next, &exit.point)) > max_length
+        {
+            continue;
+        }
+        if next == exit.point {
+            let mut route = path.clone();
+            route.push(next);
+            found.push((next_distance, route));
+            if found.len() >= limit {
+                break;
+            }
+            continue;
+        }
+        path.push(next);
+        on_path.insert(next);
+        stack.push((children(maze, next), next_distance));
+    }
+
+    found.sort();
+    Ok(found
+        .into_iter()
+        .map(|(_, path)| solution(maze, &path, decisions))
+        .collect())
+}
+
+fn children(maze: &Maze, point: Point) -> Vec<Point> {
+    let mut children: Vec<Point> = maze
+        .node_at(point)
+        .map(|node| node.children.values().copied().collect())
+        .unwrap_or_default();
+    // The children are kept in a hash map, sorted the paths come out the same every run
+    children.sort_by(|a, b| b.cmp(a));
+    children
+}
+
+fn distance(maze: &Maze, path: &[Point]) -> u32 {
+    path.windows(2)
+        .map(|pair| maze.distance(pair[0], pair[1]))
+        .sum()
+}
+
+fn solution<'a>(maze: &'a Maze, path: &[Point], decisions: u32) -> Solution<'a> {
+    let nodes: VecDeque<_> = path
+        .iter()
+        .map(|point| maze.node_at(*point).unwrap())
+        .collect();
+    Solution::new(maze, decisions, nodes)
+}
+
+// Shortest paths between two nodes with some of the maze left out, the spur searches of Yen's
+// algorithm.
+struct Search<'a, 'c> {
+    maze: &'a Maze,
+    context: &'c SolveContext,
+    decisions: u32,
+}
+
+impl Search<'_, '_> {
+    fn shortest(
+        &mut self,
+        from: Point,
+        to: Point,
+        removed_edges: &[(Point, Point)],
+        removed_nodes: &FxHashSet<Point>,
+    ) -> Result<Option<(u32, Vec<Point>)>, Aborted> {
+        let mut distances = FxHashMap::from_iter([(from, 0)]);
+        let mut previous: FxHashMap<Point, Point> = FxHashMap::default();
+        let mut queue = BinaryHeap::from([Reverse((0, from))]);
+        while let Some(Reverse((distance, point))) = queue.pop() {
+            self.decisions += 1;
+            self.context.check(self.decisions)?;
+            if point == to {
+                let mut path = vec![to];
+                while let Some(point) = previous.get(&path[path.len() - 1]) {
+                    path.push(*point);
+                }
+                path.reverse();
+                return Ok(Some((distance, path)));
+            }
+            if distance > distances[&point] {
+                continue;
+            }
+            for next in children(self.maze, point) {
+                if removed_nodes.contains(&next) || removed_edges.contains(&(point, next)) {
+                    continue;
+                }
+                let next_distance = distance + self.maze.distance(point, next);
+                if distances
+                    .get(&next)
+                    .is_none_or(|current| next_distance < *current)
+                {
+                    distances.insert(next, next_distance);
+                    previous.insert(next, point);
+                    queue.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generator::{generate, GeneratorOptions};
+    use crate::img::Image;
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+
+    use pretty_assertions::assert_eq;
+
+    // Three ways around two blocks of wall.
+    fn image() -> Image {
+        maze_image!([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ])
+    }
+
+    fn distances(solutions: &[Solution]) -> Vec<u32> {
+        solutions.iter().map(Solution::distance).collect()
+    }
+
+    #[test]
+    fn shortest_paths_first() {
+        let image = image();
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let context = SolveContext::default();
+
+        let paths = k_shortest(&maze, 20, &context).unwrap();
+        assert_eq!(vec![10, 10, 10, 10, 14, 14], distances(&paths));
+        assert!(paths.iter().all(Solution::is_complete));
+        let unique: FxHashSet<Vec<Point>> = paths.iter().map(Solution::points).collect();
+        assert_eq!(paths.len(), unique.len());
+
+        assert_eq!(
+            vec![10, 10],
+            distances(&k_shortest(&maze, 2, &context).unwrap())
+        );
+    }
+
+    #[test]
+    fn simple_paths_up_to_a_length() {
+        let image = image();
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let context = SolveContext::default();
+
+        let all = all_simple(&maze, u32::MAX, usize::MAX, &context).unwrap();
+        assert_eq!(
+            distances(&k_shortest(&maze, 100, &context).unwrap()),
+            distances(&all)
+        );
+        assert_eq!(
+            vec![10, 10, 10, 10],
+            distances(&all_simple(&maze, 12, usize::MAX, &context).unwrap())
+        );
+        assert!(all_simple(&maze, 9, usize::MAX, &context)
+            .unwrap()
+            .is_empty());
+        assert_eq!(3, all_simple(&maze, u32::MAX, 3, &context).unwrap().len());
+    }
+
+    #[test]
+    fn yen_matches_enumeration() {
+        for seed in 0..30 {
+            let image = generate(&GeneratorOptions {
+                width: 2 + seed as u32 % 4,
+                height: 2 + seed as u32 % 3,
+                seed,
+                braid: 0.5,
+            });
+            let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+            let context = SolveContext::default();
+
+            let all = distances(&all_simple(&maze, u32::MAX, usize::MAX, &context).unwrap());
+            let yen = distances(&k_shortest(&maze, 10, &context).unwrap());
+            assert_eq!(all[..all.len().min(10)], yen[..], "maze {seed}");
+        }
+    }
+}
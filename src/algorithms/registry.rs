@@ -0,0 +1,179 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+    a_star::AStar, breadth_first::BreadthFirst, depth_first::DepthFirst, dijkstra::Dijkstra,
+    left_turn::LeftTurn, Solver,
+};
+
+// A solver that can be picked by name, with the options it understands and how to build it from
+// them.
+pub struct Registration {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    pub options: &'static [&'static str],
+    build: fn(&Options) -> Result<Box<dyn Solver>, String>,
+}
+
+impl Registration {
+    pub fn build(&self, options: &Options) -> Result<Box<dyn Solver>, String> {
+        if let Some((key, _)) = options
+            .0
+            .iter()
+            .find(|(key, _)| !self.options.contains(&key.as_str()))
+        {
+            return Err(match self.options {
+                [] => format!("{} takes no options, not `{key}`", self.name),
+                known => format!(
+                    "{} has no option `{key}`, it takes {}",
+                    self.name,
+                    known.join(", ")
+                ),
+            });
+        }
+        (self.build)(options)
+    }
+}
+
+// Every solver, in the order they are listed and benchmarked. A new solver only needs an entry here.
+pub static SOLVERS: &[Registration] = &[
+    Registration {
+        name: "left-turn",
+        label: "Left Turn ◀️◀️◀️",
+        description: "Follows the wall on one hand, hand=left|right",
+        options: &["hand"],
+        build: |options| {
+            Ok(Box::new(LeftTurn {
+                hand: options.get("hand")?.unwrap_or_default(),
+            }))
+        },
+    },
+    Registration {
+        name: "dijkstra",
+        label: "Dijkstra 👴",
+        description: "Shortest path by distance",
+        options: &[],
+        build: |_| Ok(Box::new(Dijkstra)),
+    },
+    Registration {
+        name: "a-star",
+        label: "A🌟",
+        description: "Shortest path guided by an estimate of the distance to the exit, \
+            heuristic=manhattan|euclidean|chebyshev|zero|landmarks, weight=1.5",
+        options: &["heuristic", "weight"],
+        build: |options| {
+            Ok(Box::new(AStar {
+                heuristic: options.get("heuristic")?.unwrap_or_default(),
+                weight: options.get("weight")?.unwrap_or_default(),
+            }))
+        },
+    },
+    Registration {
+        name: "breadth-first",
+        label: "Breadth First 🍞",
+        description: "Path with the fewest nodes",
+        options: &[],
+        build: |_| Ok(Box::new(BreadthFirst)),
+    },
+    Registration {
+        name: "depth-first",
+        label: "Depth First",
+        description: "First path found going as deep as possible",
+        options: &[],
+        build: |_| Ok(Box::new(DepthFirst)),
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Registration> {
+    SOLVERS
+        .iter()
+        .find(|registration| registration.name == name)
+}
+
+// Options written after the name of a solver, like the `hand=right` of `left-turn:hand=right`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options(Vec<(String, String)>);
+
+impl Options {
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<Option<T>, String>
+    where
+        T::Err: Display,
+    {
+        let Some((_, value)) = self.0.iter().find(|(name, _)| name == key) else {
+            return Ok(None);
+        };
+        value
+            .parse()
+            .map(Some)
+            .map_err(|err| format!("invalid {key} `{value}`: {err}"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromStr for Options {
+    type Err = String;
+
+    fn from_str(options: &str) -> Result<Self, Self::Err> {
+        options
+            .split(':')
+            .map(|option| match option.split_once('=') {
+                Some((key, value)) if !key.is_empty() => Ok((key.into(), value.into())),
+                _ => Err(format!(
+                    "`{option}` is not an option, write it as key=value"
+                )),
+            })
+            .collect::<Result<_, _>>()
+            .map(Options)
+    }
+}
+
+impl Display for Options {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options: Vec<String> = self
+            .0
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        write!(f, "{}", options.join(":"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::Algorithm;
+
+    #[test]
+    fn parsed_algorithms() {
+        let algorithm: Algorithm = "left-turn:hand=right".parse().unwrap();
+        assert_eq!("left-turn:hand=right", algorithm.name());
+        assert_eq!("Left Turn ◀️◀️◀️ (hand=right)", algorithm.to_string());
+        assert_eq!("a-star", "a-star".parse::<Algorithm>().unwrap().name());
+
+        assert_eq!(
+            Err("`hand` is not an option, write it as key=value".to_string()),
+            "hand".parse::<Options>()
+        );
+        assert_eq!(
+            Some("dijkstra takes no options, not `hand`".to_string()),
+            "dijkstra:hand=left".parse::<Algorithm>().err()
+        );
+        assert_eq!(
+            Some("invalid hand `up`: use left or right".to_string()),
+            "left-turn:hand=up".parse::<Algorithm>().err()
+        );
+    }
+
+    #[test]
+    fn registered_names() {
+        let algorithms = Algorithm::all();
+        assert_eq!(SOLVERS.len(), algorithms.len());
+        for (registration, algorithm) in SOLVERS.iter().zip(&algorithms) {
+            assert_eq!(registration.name, algorithm.name());
+            assert!(find(registration.name).is_some());
+        }
+    }
+}
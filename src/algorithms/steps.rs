@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use super::{expansions, Solution};
+use crate::{
+    maze::Maze,
+    node::{Node, Point},
+    utils::get_node,
+};
+
+// What a search did in a decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    // A node was taken off the frontier and its neighbours looked at
+    Expanded(Point),
+    // A node was added to the frontier
+    Pushed(Point),
+    // The search reached the exit
+    Found(Point),
+}
+
+// A search that runs one decision at a time. Solvers only implement this, solving to the end and
+// stepping through the events both drive it.
+pub trait Search<'a> {
+    // Makes the next decision and records what it did, false once the search is over.
+    fn step(&mut self, events: &mut Vec<Event>) -> bool;
+
+    fn decisions(&self) -> u32;
+
+    // The path that was found, once `step` returned false.
+    fn solution(&self) -> Option<Solution<'a>>;
+}
+
+// Search for a maze without an entrance or an exit, which is over before it starts.
+pub(crate) struct Unsolvable;
+
+impl<'a> Search<'a> for Unsolvable {
+    fn step(&mut self, _events: &mut Vec<Event>) -> bool {
+        false
+    }
+
+    fn decisions(&self) -> u32 {
+        0
+    }
+
+    fn solution(&self) -> Option<Solution<'a>> {
+        None
+    }
+}
+
+// The events of a search one at a time, for anything that wants to watch a solver work.
+pub struct Steps<'a> {
+    search: Box<dyn Search<'a> + 'a>,
+    events: VecDeque<Event>,
+    buffer: Vec<Event>,
+    running: bool,
+    expanded: u32,
+}
+
+impl<'a> Steps<'a> {
+    pub fn new(search: Box<dyn Search<'a> + 'a>) -> Self {
+        Steps {
+            search,
+            events: VecDeque::new(),
+            buffer: Vec::new(),
+            running: true,
+            expanded: 0,
+        }
+    }
+
+    pub fn decisions(&self) -> u32 {
+        self.search.decisions()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.running && self.events.is_empty()
+    }
+
+    // The solution, once every event has been taken.
+    pub fn solution(&self) -> Option<Solution<'a>> {
+        if !self.is_finished() {
+            return None;
+        }
+        self.search
+            .solution()
+            .map(|solution| solution.expanded(self.expanded))
+    }
+}
+
+impl Iterator for Steps<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        // A decision can do nothing worth an event, like skipping a stale frontier entry
+        while self.events.is_empty() && self.running {
+            self.running = self.search.step(&mut self.buffer);
+            self.expanded += expansions(&self.buffer);
+            self.events.extend(self.buffer.drain(..));
+        }
+        self.events.pop_front()
+    }
+}
+
+// Follows the reverse path from the exit back to the start, the order the solvers return paths in.
+pub(crate) fn trace_back<'a>(
+    maze: &'a Maze,
+    end: &'a Node,
+    reverse_path: &[Option<&'a Node>],
+) -> VecDeque<&'a Node> {
+    let mut current = Some(end);
+    let mut path = VecDeque::new();
+    while let Some(node) = current {
+        let node = get_node(node, maze).unwrap();
+        path.push_back(node);
+        let index = (node.point.y * maze.width) + node.point.x;
+        current = reverse_path[index as usize];
+    }
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::{breadth_first::BreadthFirst, Algorithm, Solver};
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn breadth_first_events() {
+        let image = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 0, 0]
+        ]);
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+
+        let mut steps = BreadthFirst.steps(&maze);
+        let first: Vec<Event> = steps.by_ref().take(2).collect();
+        assert_eq!(
+            vec![
+                Event::Expanded(Point::at(1, 0)),
+                Event::Pushed(Point::at(1, 2))
+            ],
+            first
+        );
+        assert_eq!(None, steps.solution().map(|solution| solution.count));
+
+        let last = steps.by_ref().last();
+        assert_eq!(Some(Event::Found(Point::at(1, 4))), last);
+        assert!(steps.is_finished());
+        assert_eq!(
+            BreadthFirst.solve(&maze).unwrap().path,
+            steps.solution().unwrap().path
+        );
+    }
+
+    #[test]
+    fn steps_match_solutions() {
+        let image = maze_image!([
+            [0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 0, 0, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+
+        for algorithm in Algorithm::all() {
+            let solution = algorithm.solve(&maze).unwrap();
+            let mut steps = algorithm.steps(&maze);
+            let found = steps
+                .by_ref()
+                .filter(|event| matches!(event, Event::Found(_)))
+                .count();
+            assert_eq!(1, found, "{}", algorithm.name());
+
+            let stepped = steps.solution().unwrap();
+            assert_eq!(solution.path, stepped.path, "{}", algorithm.name());
+            assert_eq!(solution.count, stepped.count, "{}", algorithm.name());
+            assert_eq!(solution.expanded, stepped.expanded, "{}", algorithm.name());
+        }
+    }
+}
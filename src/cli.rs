@@ -0,0 +1,798 @@
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{
+    builder::{PossibleValue, TypedValueParser},
+    parser::ValueSource,
+    ArgAction, ArgMatches, Args, FromArgMatches, Parser, Subcommand, ValueEnum,
+};
+use image::{ImageFormat, Rgb};
+
+use crate::{
+    algorithms::{registry, Algorithm, SolveContext},
+    colors::{parse_color, Colormap, PathColor},
+    img::{parse_point, parse_region, Region, RenderOptions},
+    node::Point,
+    simplify::Pass,
+    svg::SvgOptions,
+    utils::get_algorithm,
+};
+
+pub const OUTPUT_FILENAME: &str = "./solution.png";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Png,
+    Bmp,
+    Gif,
+    Tiff,
+    Tga,
+    Pnm,
+    Jpeg,
+    Svg,
+}
+
+impl OutputFormat {
+    // The format matching the file extension, if it is one we can write.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            return Some(OutputFormat::Svg);
+        }
+        match ImageFormat::from_path(path).ok()? {
+            ImageFormat::Png => Some(OutputFormat::Png),
+            ImageFormat::Bmp => Some(OutputFormat::Bmp),
+            ImageFormat::Gif => Some(OutputFormat::Gif),
+            ImageFormat::Tiff => Some(OutputFormat::Tiff),
+            ImageFormat::Tga => Some(OutputFormat::Tga),
+            ImageFormat::Pnm => Some(OutputFormat::Pnm),
+            ImageFormat::Jpeg => Some(OutputFormat::Jpeg),
+            _ => None,
+        }
+    }
+
+    // The image format to encode with, `None` for svg which is written by hand.
+    pub fn raster(self) -> Option<ImageFormat> {
+        match self {
+            OutputFormat::Png => Some(ImageFormat::Png),
+            OutputFormat::Bmp => Some(ImageFormat::Bmp),
+            OutputFormat::Gif => Some(ImageFormat::Gif),
+            OutputFormat::Tiff => Some(ImageFormat::Tiff),
+            OutputFormat::Tga => Some(ImageFormat::Tga),
+            OutputFormat::Pnm => Some(ImageFormat::Pnm),
+            OutputFormat::Jpeg => Some(ImageFormat::Jpeg),
+            OutputFormat::Svg => None,
+        }
+    }
+}
+
+const LONG_DESC: &str = "
+
+┌┬┐┬ ┬┌─┐┌─┐┌─┐┬ ┬┌─┐   ┬─┐┌─┐
+ │ ├─┤├┤ └─┐├┤ │ │└─┐───├┬┘└─┐
+ ┴ ┴ ┴└─┘└─┘└─┘└─┘└─┘   ┴└─└─┘
+
+A small program that uses different algorithms to solve mazes.
+
+Mazes need to be provided as raw uncompressed images with exactly one entrance at the top and one exit
+at the bottom. The entire image needs to be surrounded by black borders and each wall and each path
+needs to be exactly one pixel wide each.
+
+there is currently no limit to how big a maze can be, but be wary of memory consumption, you have
+been warned.";
+
+#[derive(Parser)]
+#[command(
+    author,
+    version,
+    about = "Theseus-rs\n---------\nA small program that tries to solve mazes",
+    long_about = LONG_DESC,
+    args_conflicts_with_subcommands = true,
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    // Solving without a subcommand is kept for old scripts, `theseus-rs maze.png -a`
+    #[command(flatten)]
+    pub solve: SolveArgs,
+}
+
+impl Cli {
+    // Builds the arguments from the matches, then lets the command line override settings that
+    // came from the config file as defaults.
+    pub fn from_matches(matches: &ArgMatches) -> Result<Cli, clap::Error> {
+        let mut cli = Cli::from_arg_matches(matches)?;
+        match (&mut cli.command, matches.subcommand()) {
+            (Some(Command::Solve(args)), Some((_, matches))) => args.colors.resolve(matches),
+            (Some(Command::Batch(args)), Some((_, matches))) => args.colors.resolve(matches),
+            (None, _) => cli.solve.colors.resolve(matches),
+            _ => {}
+        }
+        Ok(cli)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Solve a maze and save the solution as an image
+    Solve(SolveArgs),
+    /// Generate a new maze image
+    Generate(GenerateArgs),
+    /// Check that an image follows the maze format
+    Validate(MazeArgs),
+    /// Check a solution made elsewhere against the original maze
+    Verify(VerifyArgs),
+    /// Solve every maze in a list of files, directories and glob patterns
+    Batch(BatchArgs),
+    /// Compare the run time and memory of the algorithms on one or more mazes
+    Bench(BenchArgs),
+    /// Render a maze, or its analyzed node graph, without solving it
+    Render(RenderArgs),
+    /// Print information about a maze
+    Info(MazeArgs),
+    /// Watch a solver explore a maze step by step in the terminal
+    Watch(WatchArgs),
+    /// Find the longest route through a perfect maze and the entrance and exit that make it hardest
+    Longest(LongestArgs),
+    /// Save the distance from the entrance to every pixel of a maze
+    Distances(DistancesArgs),
+    /// Lead Theseus to the exit while the Minotaur chases him, and render both their trails
+    Minotaur(MinotaurArgs),
+    /// Inspect the settings from theseus.toml
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the settings every command runs with, after applying the config files
+    Show,
+}
+
+#[derive(Args)]
+pub struct GlobalArgs {
+    #[arg(short = 's', long = "stats", global = true, help = "Print statistics")]
+    pub statistics: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        conflicts_with = "verbose",
+        help = "Only print results and errors"
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = ArgAction::Count,
+        help = "Print more about what is going on, twice for debug output"
+    )]
+    pub verbose: u8,
+
+    #[arg(long, global = true, help = "Leave out the ascii art banners")]
+    pub no_banner: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Read settings from this file on top of the user and project theseus.toml"
+    )]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct MazeArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+}
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    #[arg(help = "The original maze image")]
+    pub maze: PathBuf,
+
+    #[arg(help = "Solved image or json list of points to check, - reads it from stdin")]
+    pub solution: PathBuf,
+}
+
+#[derive(Args)]
+pub struct SolveArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: Option<PathBuf>,
+
+    #[arg(
+        long,
+        visible_alias = "alg",
+        value_parser = AlgorithmParser,
+        help = "Algorithm to solve the maze with, options follow the name like left-turn:hand=right"
+    )]
+    pub algorithm: Option<Algorithm>,
+
+    #[command(flatten)]
+    pub legacy: LegacyAlgorithmArgs,
+
+    #[arg(
+        long,
+        value_parser = AlgorithmParser,
+        value_delimiter = ',',
+        value_name = "ALGORITHMS",
+        conflicts_with = "algorithm",
+        help = "Solve with several algorithms and render all their paths into one image"
+    )]
+    pub compare: Vec<Algorithm>,
+
+    #[arg(
+        long,
+        value_name = "K",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["algorithm", "compare", "all_paths"],
+        help = "Find the K shortest paths without loops and render them into one image"
+    )]
+    pub paths: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "LENGTH",
+        conflicts_with_all = ["algorithm", "compare"],
+        help = "Find every path without loops up to LENGTH pixels and render them into one image"
+    )]
+    pub all_paths: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 100,
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "all_paths",
+        help = "Stop looking for paths with --all-paths once N of them are found"
+    )]
+    pub max_paths: u32,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[command(flatten)]
+    pub colors: SolutionColorArgs,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ReportFormat::Text,
+        help = "Print the results as text or as a single json document"
+    )]
+    pub format: ReportFormat,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = PathPoints::Nodes,
+        help = "Which points of the solution to include in json output"
+    )]
+    pub points: PathPoints,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Also write the solution path as json to this file"
+    )]
+    pub path_output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write every step of the solver as a line of json to this file"
+    )]
+    pub trace: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        value_name = "PASSES",
+        num_args = 0..,
+        default_missing_values = ["prune", "collapse", "dedupe"],
+        conflicts_with_all = ["paths", "all_paths"],
+        help = "Shrink the node graph before solving, with every pass unless some are listed"
+    )]
+    pub simplify: Option<Vec<Pass>>,
+}
+
+impl SolveArgs {
+    // The algorithm picked with `--algorithm` or one of the deprecated flags. The two conflict on
+    // the command line, but `--algorithm` can also come from theseus.toml.
+    pub fn algorithm(&self) -> Option<Algorithm> {
+        let Some(algorithm) = get_algorithm(&self.legacy) else {
+            return self.algorithm.clone();
+        };
+        eprintln!(
+            "warning: the single algorithm flags are deprecated, use `--algorithm {}` instead",
+            algorithm.name()
+        );
+        Some(algorithm)
+    }
+}
+
+// Parses an algorithm from the registry, which also provides the possible values listed in the help.
+#[derive(Clone)]
+pub struct AlgorithmParser;
+
+impl TypedValueParser for AlgorithmParser {
+    type Value = Algorithm;
+
+    fn parse_ref(
+        &self,
+        command: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Algorithm, clap::Error> {
+        let parse = |value: &str| value.parse::<Algorithm>();
+        parse.parse_ref(command, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let values = registry::SOLVERS
+            .iter()
+            .map(|solver| PossibleValue::new(solver.name).help(solver.description));
+        Some(Box::new(values))
+    }
+}
+
+// The flags used to pick an algorithm before `--algorithm` existed, only one at a time.
+#[derive(Args)]
+#[group(multiple = false)]
+pub struct LegacyAlgorithmArgs {
+    #[arg(short = 'j', long, hide = true, conflicts_with = "algorithm")]
+    pub dijkstra: bool,
+
+    #[arg(short, long, hide = true, conflicts_with = "algorithm")]
+    pub a_star: bool,
+
+    #[arg(short, long, hide = true, conflicts_with = "algorithm")]
+    pub left_turn: bool,
+
+    #[arg(short, long, hide = true, conflicts_with = "algorithm")]
+    pub breadth_first: bool,
+
+    #[arg(short, long, hide = true, conflicts_with = "algorithm")]
+    pub depth_first: bool,
+}
+
+#[derive(Args)]
+pub struct OutputArgs {
+    #[arg(short, long, help = "Set output image filename, - writes to stdout")]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Format of the output image, guessed from the output filename if not set"
+    )]
+    pub output_format: Option<OutputFormat>,
+
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..), help = "Scale factor of the output, each maze pixel becomes a block of this size")]
+    pub scale: u32,
+
+    #[arg(
+        long,
+        help = "Width of the solution line in png output, in output pixels (defaults to the scale)"
+    )]
+    pub line_width: Option<u32>,
+
+    #[arg(long, value_parser = parse_region, value_name = "X,Y,WIDTH,HEIGHT", help = "Crop the output to a region of the maze")]
+    pub crop: Option<Region>,
+
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        help = "Width of the solution line in svg output, in maze pixels"
+    )]
+    pub stroke_width: f32,
+
+    #[arg(long, value_parser = parse_color, help = "Color of the walls in the output")]
+    pub wall_color: Option<Rgb<u8>>,
+
+    #[arg(long, value_parser = parse_color, help = "Color of the pathways in the output")]
+    pub path_color: Option<Rgb<u8>>,
+}
+
+impl OutputArgs {
+    pub fn output(&self) -> &Path {
+        self.output_or(OUTPUT_FILENAME)
+    }
+
+    pub fn output_or<'a>(&'a self, default: &'a str) -> &'a Path {
+        self.output.as_deref().unwrap_or(Path::new(default))
+    }
+
+    // `--output-format`, or else the format of the output extension. Png when neither says, like
+    // when writing to stdout.
+    pub fn format(&self, output: &Path) -> OutputFormat {
+        self.output_format
+            .or_else(|| OutputFormat::from_path(output))
+            .unwrap_or(OutputFormat::Png)
+    }
+
+    pub fn svg_options(&self, solution: PathColor) -> SvgOptions {
+        let defaults = SvgOptions::default();
+        SvgOptions {
+            scale: self.scale,
+            wall: self.wall_color.unwrap_or(defaults.wall),
+            path: self.path_color.unwrap_or(defaults.path),
+            solution,
+            stroke_width: self.stroke_width,
+            crop: self.crop,
+        }
+    }
+
+    pub fn render_options(&self) -> RenderOptions {
+        let defaults = RenderOptions::default();
+        RenderOptions {
+            scale: self.scale,
+            line_width: self.line_width,
+            crop: self.crop,
+            wall: self.wall_color.unwrap_or(defaults.wall),
+            path: self.path_color.unwrap_or(defaults.path),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct SolutionColorArgs {
+    #[arg(long, value_parser = parse_color, help = "Color of the solution path, a name or #rrggbb")]
+    pub color: Option<Rgb<u8>>,
+
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["FROM", "TO"],
+        value_parser = parse_color,
+        conflicts_with_all = ["color", "colormap"],
+        help = "Color the solution as a gradient from the entrance to the exit"
+    )]
+    pub gradient: Option<Vec<Rgb<u8>>>,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "color",
+        help = "Color the solution using a named colormap from the entrance to the exit"
+    )]
+    pub colormap: Option<Colormap>,
+}
+
+impl SolutionColorArgs {
+    // A color given on the command line replaces the ones from the config file, which would
+    // otherwise win by coming first below.
+    fn resolve(&mut self, matches: &ArgMatches) {
+        let given = |id| matches.value_source(id) == Some(ValueSource::CommandLine);
+        if !(given("color") || given("gradient") || given("colormap")) {
+            return;
+        }
+        if !given("color") {
+            self.color = None;
+        }
+        if !given("gradient") {
+            self.gradient = None;
+        }
+        if !given("colormap") {
+            self.colormap = None;
+        }
+    }
+
+    pub fn path_color(&self) -> PathColor {
+        if let Some(gradient) = &self.gradient {
+            PathColor::Gradient(gradient[0], gradient[1])
+        } else if let Some(colormap) = self.colormap {
+            PathColor::Colormap(colormap)
+        } else if let Some(color) = self.color {
+            PathColor::Solid(color)
+        } else {
+            PathColor::default()
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    #[arg(long, default_value_t = 50, help = "Width of the maze in cells")]
+    pub width: u32,
+
+    #[arg(long, default_value_t = 50, help = "Height of the maze in cells")]
+    pub height: u32,
+
+    #[arg(long, help = "Seed for a reproducible maze, random if not set")]
+    pub seed: Option<u64>,
+
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        help = "Share of dead ends to open up into loops, between 0.0 (perfect maze) and 1.0"
+    )]
+    pub braid: f32,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    #[arg(
+        required = true,
+        value_name = "PATHS",
+        help = "Maze images, directories of images or glob patterns like 'mazes/*.png'"
+    )]
+    pub inputs: Vec<String>,
+
+    #[arg(
+        long,
+        visible_alias = "algorithm",
+        value_parser = AlgorithmParser,
+        value_delimiter = ',',
+        value_name = "ALGORITHMS",
+        required = true,
+        help = "Algorithms to solve every maze with"
+    )]
+    pub algorithms: Vec<Algorithm>,
+
+    #[arg(
+        long,
+        default_value = "{stem}_{algorithm}.png",
+        help = "Filename of each solution, {stem} is replaced by the maze filename without extension, {dir} by the name of its directory and {algorithm} by the algorithm"
+    )]
+    pub template: String,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        default_value = ".",
+        help = "Directory to write the solutions to"
+    )]
+    pub output_dir: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = 1,
+        help = "Number of mazes to solve at the same time"
+    )]
+    pub jobs: usize,
+
+    #[command(flatten)]
+    pub colors: SolutionColorArgs,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+}
+
+// Bounds on every run of a solver, a solver that hits one gives up without a solution.
+#[derive(Args)]
+pub struct LimitArgs {
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        help = "Give up solving after this long, like 30s, 500ms or 2m"
+    )]
+    pub timeout: Option<Duration>,
+
+    #[arg(
+        long,
+        value_name = "DECISIONS",
+        help = "Give up solving after this many decisions"
+    )]
+    pub max_steps: Option<u32>,
+}
+
+impl LimitArgs {
+    // The deadline starts counting when the context is created, so create one per run.
+    pub fn context(&self) -> SolveContext {
+        SolveContext::new(self.timeout, self.max_steps)
+    }
+}
+
+// A number with an optional unit of ms, s, m or h, seconds without one.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("`{value}` is not a duration"))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        unit => return Err(format!("unknown unit `{unit}`, use ms, s, m or h")),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("`{value}` is too long a duration"))
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    #[arg(required = true)]
+    pub filenames: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_parser = AlgorithmParser,
+        value_delimiter = ',',
+        value_name = "ALGORITHMS",
+        help = "Algorithms to run, all of them if not set"
+    )]
+    pub algorithms: Vec<Algorithm>,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = 5,
+        help = "Number of times to run each algorithm"
+    )]
+    pub repeat: u32,
+
+    #[arg(long, value_enum, default_value_t = BenchFormat::Table, help = "Format of the results")]
+    pub format: BenchFormat,
+
+    #[arg(
+        short,
+        long,
+        help = "Write the results to a file instead of printing them"
+    )]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+// The node points are the corners and junctions the solver walked through, the pixels the full
+// route between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PathPoints {
+    Nodes,
+    Pixels,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BenchFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Args)]
+pub struct RenderArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+
+    #[arg(long, help = "Render the analyzed node graph, to verify the analyzer")]
+    pub graph: bool,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+
+    #[arg(
+        long,
+        visible_alias = "alg",
+        value_parser = AlgorithmParser,
+        default_value = "a-star",
+        help = "Algorithm to watch, options follow the name like left-turn:hand=right"
+    )]
+    pub algorithm: Algorithm,
+
+    #[arg(
+        long,
+        default_value_t = 16,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Solver events shown per frame, [ and ] change it while watching"
+    )]
+    pub speed: u32,
+}
+
+#[derive(Args)]
+pub struct LongestArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Save the maze with its entrance and exit moved to the ends of the hardest route"
+    )]
+    pub rewrite: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ReportFormat::Text,
+        help = "Print the routes as text or as a single json document"
+    )]
+    pub format: ReportFormat,
+}
+
+// How `distances` saves the distance field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DistanceExport {
+    /// The maze colored with a colormap from the entrance to the farthest pixel
+    Color,
+    /// A 16 bit grayscale png with the distance as the value of each pixel
+    Gray16,
+    /// Every distance as a little endian u32, row by row
+    Raw,
+}
+
+#[derive(Args)]
+pub struct DistancesArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+
+    #[arg(short, long, help = "Set output filename, - writes to stdout")]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = DistanceExport::Color,
+        help = "Save the distances as a colored image, a 16 bit grayscale png or raw u32 values"
+    )]
+    pub export: DistanceExport,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Colormap::Viridis,
+        help = "Colormap for the colored image"
+    )]
+    pub colormap: Colormap,
+}
+
+#[derive(Args)]
+pub struct MinotaurArgs {
+    #[arg(help = "Maze image, - reads it from stdin")]
+    pub filename: PathBuf,
+
+    #[arg(
+        long,
+        value_name = "X,Y",
+        value_parser = parse_point,
+        help = "Pixel the Minotaur starts on"
+    )]
+    pub minotaur: Point,
+
+    #[arg(
+        long,
+        default_value_t = 2,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Steps the Minotaur takes for every step of Theseus"
+    )]
+    pub steps: u32,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[command(flatten)]
+    pub limits: LimitArgs,
+}
@@ -0,0 +1,161 @@
+use clap::ValueEnum;
+use image::Rgb;
+
+pub const RED: Rgb<u8> = Rgb([255, 0, 0]);
+
+// Parses either a named color or a hex triplet written as `#rrggbb` or `rrggbb`.
+pub fn parse_color(value: &str) -> Result<Rgb<u8>, String> {
+    let named = match value.to_lowercase().as_str() {
+        "black" => Some([0, 0, 0]),
+        "white" => Some([255, 255, 255]),
+        "red" => Some([255, 0, 0]),
+        "green" => Some([0, 255, 0]),
+        "blue" => Some([0, 0, 255]),
+        "yellow" => Some([255, 255, 0]),
+        "cyan" => Some([0, 255, 255]),
+        "magenta" => Some([255, 0, 255]),
+        "orange" => Some([255, 165, 0]),
+        "purple" => Some([128, 0, 128]),
+        "gray" | "grey" => Some([128, 128, 128]),
+        _ => None,
+    };
+    if let Some(channels) = named {
+        return Ok(Rgb(channels));
+    }
+
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid color '{value}', expected a name or a hex value like #ff0000"
+        ));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    Ok(Rgb([channel(0), channel(2), channel(4)]))
+}
+
+pub fn lerp(from: Rgb<u8>, to: Rgb<u8>, t: f32) -> Rgb<u8> {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Rgb([
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Colormap {
+    Viridis,
+    Magma,
+    Plasma,
+    Rainbow,
+}
+
+impl Colormap {
+    fn stops(&self) -> &'static [[u8; 3]] {
+        match self {
+            Colormap::Viridis => &[
+                [68, 1, 84],
+                [59, 82, 139],
+                [33, 145, 140],
+                [94, 201, 98],
+                [253, 231, 37],
+            ],
+            Colormap::Magma => &[
+                [0, 0, 4],
+                [81, 18, 124],
+                [183, 55, 121],
+                [252, 137, 97],
+                [252, 253, 191],
+            ],
+            Colormap::Plasma => &[
+                [13, 8, 135],
+                [126, 3, 168],
+                [204, 71, 120],
+                [248, 149, 64],
+                [240, 249, 33],
+            ],
+            Colormap::Rainbow => &[
+                [255, 0, 0],
+                [255, 255, 0],
+                [0, 255, 0],
+                [0, 255, 255],
+                [0, 0, 255],
+            ],
+        }
+    }
+
+    // Samples the colormap at `t`, where 0.0 is the first stop and 1.0 the last.
+    pub fn sample(&self, t: f32) -> Rgb<u8> {
+        let stops = self.stops();
+        let scaled = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (scaled.floor() as usize).min(stops.len() - 2);
+        lerp(
+            Rgb(stops[index]),
+            Rgb(stops[index + 1]),
+            scaled - index as f32,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathColor {
+    Solid(Rgb<u8>),
+    Gradient(Rgb<u8>, Rgb<u8>),
+    Colormap(Colormap),
+}
+
+impl PathColor {
+    // Color at `t` along the path, 0.0 being the entrance and 1.0 the exit.
+    pub fn at(&self, t: f32) -> Rgb<u8> {
+        match self {
+            PathColor::Solid(color) => *color,
+            PathColor::Gradient(from, to) => lerp(*from, *to, t),
+            PathColor::Colormap(colormap) => colormap.sample(t),
+        }
+    }
+}
+
+impl Default for PathColor {
+    fn default() -> Self {
+        PathColor::Solid(RED)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Ok(Rgb([255, 128, 0])), parse_color("#ff8000"));
+        assert_eq!(Ok(Rgb([0, 16, 171])), parse_color("0010AB"));
+    }
+
+    #[test]
+    fn parse_named() {
+        assert_eq!(Ok(Rgb([0, 0, 255])), parse_color("Blue"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_color("#ff80").is_err());
+        assert!(parse_color("#gg0000").is_err());
+        assert!(parse_color("reddish").is_err());
+    }
+
+    #[test]
+    fn gradient_endpoints() {
+        let color = PathColor::Gradient(Rgb([0, 0, 0]), Rgb([255, 255, 255]));
+        assert_eq!(Rgb([0, 0, 0]), color.at(0.0));
+        assert_eq!(Rgb([128, 128, 128]), color.at(0.5));
+        assert_eq!(Rgb([255, 255, 255]), color.at(1.0));
+    }
+
+    #[test]
+    fn colormap_endpoints() {
+        assert_eq!(Rgb([68, 1, 84]), Colormap::Viridis.sample(0.0));
+        assert_eq!(Rgb([253, 231, 37]), Colormap::Viridis.sample(1.0));
+        assert_eq!(Rgb([0, 255, 0]), Colormap::Rainbow.sample(0.5));
+    }
+}
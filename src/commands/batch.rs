@@ -0,0 +1,380 @@
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process::exit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use image::ImageFormat;
+
+use crate::{
+    algorithms::{Aborted, Algorithm},
+    cli::{BatchArgs, OutputFormat},
+    commands::{fail, format_error, ABORTED, ERROR, NO_SOLUTION},
+    img::{Image, RenderOptions},
+    log::info,
+    maze::Maze,
+    statistics::Statistics,
+    svg::{self, SvgOptions},
+};
+
+// What happened to one maze, the solve time of every algorithm that found a solution, the ones
+// that didn't or gave up and the reasons the maze couldn't be solved at all.
+struct Outcome {
+    maze: PathBuf,
+    load: Duration,
+    solved: Vec<(Algorithm, Duration)>,
+    unsolved: Vec<Algorithm>,
+    aborted: Vec<(Algorithm, Aborted)>,
+    errors: Vec<String>,
+}
+
+impl Outcome {
+    fn failed(&self) -> bool {
+        !self.errors.is_empty() || !self.unsolved.is_empty() || !self.aborted.is_empty()
+    }
+
+    fn problems(&self) -> String {
+        let unsolved = self
+            .unsolved
+            .iter()
+            .map(|algorithm| format!("{} found no solution", algorithm.name()));
+        let aborted = self
+            .aborted
+            .iter()
+            .map(|(algorithm, aborted)| format!("{} {aborted}", algorithm.name()));
+        self.errors
+            .iter()
+            .cloned()
+            .chain(unsolved)
+            .chain(aborted)
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+pub fn run(args: &BatchArgs) {
+    let mazes = expand(&args.inputs).unwrap_or_else(|err| fail(err));
+    if mazes.is_empty() {
+        fail("No maze images were found");
+    }
+    if args.algorithms.len() > 1 && !args.template.contains("{algorithm}") {
+        fail("The template needs an {algorithm} placeholder when solving with several algorithms");
+    }
+    if mazes.len() > 1 && !args.template.contains("{stem}") {
+        fail("The template needs a {stem} placeholder when solving several mazes");
+    }
+    if let Some((first, second, name)) = collision(&mazes, args) {
+        fail(format!(
+            "{} and {} would both be saved as {name}, add {{dir}} to the template to tell them apart",
+            first.display(),
+            second.display()
+        ));
+    }
+    fs::create_dir_all(&args.output_dir).unwrap_or_else(|err| fail(err));
+
+    let jobs = args.jobs.clamp(1, mazes.len());
+    info!("solving {} mazes with {jobs} jobs", mazes.len());
+
+    // Crashes are reported with the failed mazes, keep them from printing in between. The hook is
+    // put back once the workers are done.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let start = Instant::now();
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut outcomes = thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, mazes) = (&next, &mazes);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(maze) = mazes.get(index) else {
+                    break;
+                };
+                sender.send((index, solve(maze, args))).unwrap();
+            });
+        }
+        drop(sender);
+
+        let mut outcomes = Vec::with_capacity(mazes.len());
+        for (index, outcome) in receiver {
+            print_outcome(&outcome);
+            outcomes.push((index, outcome));
+        }
+        outcomes
+    });
+    panic::set_hook(hook);
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let failed: Vec<&Outcome> = outcomes
+        .iter()
+        .map(|(_, outcome)| outcome)
+        .filter(|outcome| outcome.failed())
+        .collect();
+    info!();
+    println!(
+        "solved {} of {} mazes in {:?}",
+        outcomes.len() - failed.len(),
+        outcomes.len(),
+        start.elapsed()
+    );
+    if failed.is_empty() {
+        return;
+    }
+    println!("failed:");
+    for outcome in &failed {
+        println!("  {}: {}", outcome.maze.display(), outcome.problems());
+    }
+    if failed.iter().any(|outcome| !outcome.errors.is_empty()) {
+        exit(ERROR);
+    }
+    if failed.iter().any(|outcome| !outcome.aborted.is_empty()) {
+        exit(ABORTED);
+    }
+    exit(NO_SOLUTION);
+}
+
+fn print_outcome(outcome: &Outcome) {
+    let timings: Vec<String> = outcome
+        .solved
+        .iter()
+        .map(|(algorithm, duration)| format!("{} {:?}", algorithm.name(), duration))
+        .collect();
+    if !outcome.failed() {
+        info!(
+            "✅ {} loaded in {:?}, {}",
+            outcome.maze.display(),
+            outcome.load,
+            timings.join(", ")
+        );
+    } else {
+        info!("❌ {}: {}", outcome.maze.display(), outcome.problems());
+    }
+}
+
+// Solves a single maze with every algorithm. A maze the analyzer or a solver chokes on only fails
+// that maze, the rest of the batch carries on.
+fn solve(filename: &Path, args: &BatchArgs) -> Outcome {
+    let mut outcome = Outcome {
+        maze: filename.to_path_buf(),
+        load: Duration::ZERO,
+        solved: Vec::new(),
+        unsolved: Vec::new(),
+        aborted: Vec::new(),
+        errors: Vec::new(),
+    };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solve_all(filename, args, &mut outcome)));
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => outcome.errors.push(err),
+        Err(panic) => outcome.errors.push(panic_message(panic)),
+    }
+    outcome
+}
+
+fn solve_all(filename: &Path, args: &BatchArgs, outcome: &mut Outcome) -> Result<(), String> {
+    let start = Instant::now();
+    let image = Image::open(filename).map_err(|err| err.to_string())?;
+    format_error(&image)?;
+    let maze =
+        Maze::from_image(&image, &mut Statistics::default()).map_err(|err| err.to_string())?;
+    outcome.load = start.elapsed();
+
+    let color = args.colors.path_color();
+    for algorithm in &args.algorithms {
+        let start = Instant::now();
+        let solution = match algorithm.solve_within(&maze, &args.limits.context()) {
+            Ok(Some(solution)) => solution,
+            Ok(None) => {
+                outcome.unsolved.push(algorithm.clone());
+                continue;
+            }
+            Err(aborted) => {
+                outcome.aborted.push((algorithm.clone(), aborted));
+                continue;
+            }
+        };
+        let duration = start.elapsed();
+
+        let output = args
+            .output_dir
+            .join(output_name(&args.template, filename, algorithm));
+        let format = OutputFormat::from_path(&output).unwrap_or(OutputFormat::Png);
+        if let Some(format) = format.raster() {
+            image
+                .render(Some(&solution), &color, &RenderOptions::default())?
+                .save(&output, format)
+                .map_err(|err| err.to_string())?;
+        } else {
+            let options = SvgOptions {
+                solution: color,
+                ..SvgOptions::default()
+            };
+            fs::write(&output, svg::render(&image, Some(&solution), &options)?)
+                .map_err(|err| err.to_string())?;
+        }
+        outcome.solved.push((algorithm.clone(), duration));
+    }
+    Ok(())
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".into());
+    format!("crashed: {message}")
+}
+
+// Two mazes whose solutions would get the same name, the later one overwriting the other.
+fn collision<'a>(mazes: &'a [PathBuf], args: &BatchArgs) -> Option<(&'a Path, &'a Path, String)> {
+    let mut names: HashMap<String, &Path> = HashMap::new();
+    for maze in mazes {
+        for algorithm in &args.algorithms {
+            let name = output_name(&args.template, maze, algorithm);
+            if let Some(other) = names.insert(name.clone(), maze) {
+                return Some((other, maze, name));
+            }
+        }
+    }
+    None
+}
+
+fn output_name(template: &str, maze: &Path, algorithm: &Algorithm) -> String {
+    let stem = maze.file_stem().unwrap_or_default().to_string_lossy();
+    let dir = maze
+        .parent()
+        .and_then(Path::file_name)
+        .unwrap_or_default()
+        .to_string_lossy();
+    // Options of the algorithm are kept in the name, without the characters filenames can't have
+    template
+        .replace("{stem}", &stem)
+        .replace("{dir}", &dir)
+        .replace("{algorithm}", &algorithm.name().replace([':', '='], "-"))
+}
+
+// Turns the inputs into a list of files. Directories add every image directly inside them and
+// `*` and `?` are matched against the filenames of a directory.
+fn expand(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = Path::new(input);
+        let mut found = if input.contains(['*', '?']) {
+            let pattern = path.file_name().unwrap_or_default().to_string_lossy();
+            let directory = match path.parent() {
+                Some(parent) if parent != Path::new("") => parent,
+                _ => Path::new("."),
+            };
+            if directory.to_string_lossy().contains(['*', '?']) {
+                return Err(format!(
+                    "{input}: wildcards are only supported in the filename"
+                ));
+            }
+            list(directory, |file| {
+                file.file_name()
+                    .is_some_and(|name| matches(&pattern, &name.to_string_lossy()))
+            })?
+        } else if path.is_dir() {
+            list(path, |file| ImageFormat::from_path(file).is_ok())?
+        } else {
+            vec![path.to_path_buf()]
+        };
+        files.append(&mut found);
+    }
+
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    Ok(files)
+}
+
+fn list(directory: &Path, keep: impl Fn(&Path) -> bool) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(directory).map_err(|err| format!("{}: {err}", directory.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|file| file.is_file() && keep(file))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// Glob match where `*` is any number of characters and `?` exactly one.
+fn matches(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), name.chars().collect());
+    // matched[j] is true when the pattern so far matches the first j characters of the name
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+    for token in pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match token {
+                '*' => matched[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matched[j - 1],
+                c => j > 0 && matched[j - 1] && name[j - 1] == c,
+            };
+        }
+        matched = next;
+    }
+    matched[name.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glob_matching() {
+        assert!(matches("*.png", "perfect5.png"));
+        assert!(matches("perfect?.png", "perfect5.png"));
+        assert!(matches("*", ""));
+        assert!(matches("b*d*.png", "braid200.png"));
+        assert!(!matches("perfect?.png", "perfect99.png"));
+        assert!(!matches("*.png", "perfect5.png.bak"));
+    }
+
+    #[test]
+    fn expanded_inputs() {
+        let mazes = expand(&["mazes/perfect?.png".into(), "mazes/perfect5.png".into()]).unwrap();
+        assert_eq!(
+            vec![
+                PathBuf::from("mazes/perfect5.png"),
+                PathBuf::from("mazes/perfect7.png")
+            ],
+            mazes
+        );
+        assert_eq!(
+            "perfect5_a-star.svg",
+            output_name(
+                "{stem}_{algorithm}.svg",
+                &mazes[0],
+                &"a-star".parse().unwrap()
+            )
+        );
+        assert_eq!(
+            "mazes_perfect5.png",
+            output_name("{dir}_{stem}.png", &mazes[0], &"a-star".parse().unwrap())
+        );
+        assert_eq!(
+            "perfect5_left-turn-hand-right.png",
+            output_name(
+                "{stem}_{algorithm}.png",
+                &mazes[0],
+                &"left-turn:hand=right".parse().unwrap()
+            )
+        );
+    }
+}
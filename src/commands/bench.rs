@@ -0,0 +1,226 @@
+use std::{fmt::Write, fs, path::Path, time::Duration, time::Instant};
+
+use serde::Serialize;
+
+use crate::{
+    algorithms::Algorithm,
+    alloc,
+    cli::{BenchArgs, BenchFormat},
+    commands::{analysis_error, check_format, fail, open, print_stdout},
+    log::{self, Level},
+    maze::Maze,
+    statistics::Statistics,
+};
+
+#[derive(Debug, Serialize)]
+struct BenchResult {
+    maze: String,
+    algorithm: String,
+    runs: u32,
+    solved: bool,
+    mean_ms: f64,
+    median_ms: f64,
+    min_ms: f64,
+    decisions: u32,
+    expanded: u32,
+    solution_length: usize,
+    peak_memory_bytes: usize,
+}
+
+pub fn run(args: &BenchArgs) {
+    let algorithms: Vec<Algorithm> = if args.algorithms.is_empty() {
+        Algorithm::all()
+    } else {
+        args.algorithms.clone()
+    };
+    let runs = args.repeat.max(1);
+
+    let mut results = Vec::new();
+    for filename in &args.filenames {
+        // Analyzing is done once per maze, only the solvers are timed
+        if log::enabled(Level::Info) {
+            eprintln!("analyzing maze: {}", filename.display());
+        }
+        let image = open(filename);
+        check_format(filename, &image);
+        let maze = Maze::from_image(&image, &mut Statistics::default())
+            .unwrap_or_else(|err| fail(analysis_error(filename, err)));
+        drop(image);
+
+        for algorithm in &algorithms {
+            if log::enabled(Level::Info) {
+                eprintln!("running {} {runs} times", algorithm.name());
+            }
+            results.push(bench(filename, &maze, algorithm, runs));
+        }
+    }
+
+    let report = match args.format {
+        BenchFormat::Table => table(&results),
+        BenchFormat::Csv => csv(&results),
+        BenchFormat::Json => serde_json::to_string_pretty(&results).unwrap() + "\n",
+    };
+    match &args.output {
+        Some(output) => {
+            fs::write(output, report).unwrap_or_else(|err| fail(err));
+            if log::enabled(Level::Info) {
+                eprintln!("💾Saved benchmark to file: {}", output.display());
+            }
+        }
+        None => print_stdout(&report),
+    }
+}
+
+fn bench(filename: &Path, maze: &Maze, algorithm: &Algorithm, runs: u32) -> BenchResult {
+    let mut durations = Vec::with_capacity(runs as usize);
+    let mut peak_memory = 0;
+    let mut outcome = None;
+
+    for _ in 0..runs {
+        let baseline = alloc::reset_peak();
+        let start = Instant::now();
+        let result = algorithm.solve(maze);
+        durations.push(start.elapsed());
+        peak_memory = peak_memory.max(alloc::peak() - baseline);
+        outcome = result.map(|solution| (solution.count, solution.expanded, solution.length));
+    }
+
+    let (mean, median, min) = summarize(&mut durations);
+    let millis = |duration: Duration| duration.as_nanos() as f64 / 1e6;
+    BenchResult {
+        maze: filename.display().to_string(),
+        algorithm: algorithm.name().to_string(),
+        runs,
+        solved: outcome.is_some(),
+        mean_ms: millis(mean),
+        median_ms: millis(median),
+        min_ms: millis(min),
+        decisions: outcome.map_or(0, |(decisions, _, _)| decisions),
+        expanded: outcome.map_or(0, |(_, expanded, _)| expanded),
+        solution_length: outcome.map_or(0, |(_, _, length)| length),
+        peak_memory_bytes: peak_memory,
+    }
+}
+
+// Mean, median and minimum of the durations.
+fn summarize(durations: &mut [Duration]) -> (Duration, Duration, Duration) {
+    durations.sort();
+    let total: Duration = durations.iter().sum();
+    let mean = total / durations.len() as u32;
+    let middle = durations.len() / 2;
+    let median = if durations.len().is_multiple_of(2) {
+        (durations[middle - 1] + durations[middle]) / 2
+    } else {
+        durations[middle]
+    };
+    (mean, median, durations[0])
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 30 => format!("{:.1} GiB", b as f64 / (1u64 << 30) as f64),
+        b if b >= 1 << 20 => format!("{:.1} MiB", b as f64 / (1u64 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KiB", b as f64 / (1u64 << 10) as f64),
+        b => format!("{b} B"),
+    }
+}
+
+fn table(results: &[BenchResult]) -> String {
+    let mut table = String::new();
+    let mut maze = None;
+    // Wide enough for names with options, like a-star:heuristic=landmarks
+    let width = results
+        .iter()
+        .map(|result| result.algorithm.chars().count())
+        .fold(16, usize::max);
+    for result in results {
+        if maze != Some(&result.maze) {
+            maze = Some(&result.maze);
+            writeln!(table, "\n{} ({} runs)", result.maze, result.runs).unwrap();
+            writeln!(
+                table,
+                "{:<width$} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>12}",
+                "algorithm",
+                "mean",
+                "median",
+                "min",
+                "decisions",
+                "expanded",
+                "length",
+                "peak memory"
+            )
+            .unwrap();
+        }
+        if !result.solved {
+            writeln!(table, "{:<width$} {:>12}", result.algorithm, "no solution").unwrap();
+            continue;
+        }
+        writeln!(
+            table,
+            "{:<width$} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>12}",
+            result.algorithm,
+            format!("{:.3}ms", result.mean_ms),
+            format!("{:.3}ms", result.median_ms),
+            format!("{:.3}ms", result.min_ms),
+            result.decisions,
+            result.expanded,
+            result.solution_length,
+            format_bytes(result.peak_memory_bytes)
+        )
+        .unwrap();
+    }
+    table
+}
+
+fn csv(results: &[BenchResult]) -> String {
+    let mut csv = String::from(
+        "maze,algorithm,runs,solved,mean_ms,median_ms,min_ms,decisions,expanded,solution_length,peak_memory_bytes\n",
+    );
+    for r in results {
+        writeln!(
+            csv,
+            "\"{}\",{},{},{},{:.6},{:.6},{:.6},{},{},{},{}",
+            r.maze.replace('"', "\"\""),
+            r.algorithm,
+            r.runs,
+            r.solved,
+            r.mean_ms,
+            r.median_ms,
+            r.min_ms,
+            r.decisions,
+            r.expanded,
+            r.solution_length,
+            r.peak_memory_bytes
+        )
+        .unwrap();
+    }
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let mut durations = [4, 1, 3, 2].map(Duration::from_millis);
+        assert_eq!(
+            (
+                Duration::from_micros(2500),
+                Duration::from_micros(2500),
+                Duration::from_millis(1)
+            ),
+            summarize(&mut durations)
+        );
+
+        let mut durations = [5, 1, 9].map(Duration::from_millis);
+        assert_eq!(Duration::from_millis(5), summarize(&mut durations).1);
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("2.0 MiB", format_bytes(2 << 20));
+    }
+}
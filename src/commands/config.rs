@@ -0,0 +1,71 @@
+use clap::{Arg, ArgAction, Command, CommandFactory};
+
+use crate::{
+    cli::Cli,
+    config::{Config, FILENAME},
+};
+
+// Prints the default of every option per command, marking the ones that come from a config file.
+pub fn show(config: &Config, command: &Command) {
+    if config.files.is_empty() {
+        println!("# no {FILENAME} found, showing the built in defaults");
+    }
+    for file in &config.files {
+        println!("# {}", file.display());
+    }
+
+    let builtin = Cli::command();
+    print_section("global", command, &builtin, true);
+    for subcommand in builtin.get_subcommands() {
+        if subcommand.get_name() == "config" {
+            continue;
+        }
+        let configured = command.find_subcommand(subcommand.get_name()).unwrap();
+        print_section(subcommand.get_name(), configured, subcommand, false);
+    }
+}
+
+// Lists the options in the order of the built in command, which setting defaults shuffles.
+fn print_section(name: &str, command: &Command, builtin: &Command, global: bool) {
+    println!("\n[{name}]");
+    for arg in builtin.get_arguments() {
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        if arg.is_global_set() != global
+            || arg.is_hide_set()
+            || matches!(arg.get_action(), ArgAction::Help | ArgAction::Version)
+        {
+            continue;
+        }
+        let builtin_value = default(arg);
+        let value = command
+            .get_arguments()
+            .find(|other| other.get_id() == arg.get_id())
+            .and_then(default);
+        let configured = value != builtin_value;
+        let value = value.unwrap_or_else(|| "not set".into());
+        if configured {
+            println!("{long} = {value}  # from {FILENAME}");
+        } else {
+            println!("{long} = {value}");
+        }
+    }
+}
+
+fn default(arg: &Arg) -> Option<String> {
+    let values: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect();
+    if !values.is_empty() {
+        return Some(values.join(", "));
+    }
+    // Clap only fills in the defaults of flags once the command is built
+    match arg.get_action() {
+        ArgAction::SetTrue => Some("false".into()),
+        ArgAction::Count => Some("0".into()),
+        _ => None,
+    }
+}
@@ -0,0 +1,92 @@
+use std::{path::Path, process::exit};
+
+use crate::{
+    algorithms::{
+        dijkstra::Dijkstra,
+        distances::{encode_gray16, DistanceField},
+        Solver,
+    },
+    cli::{DistanceExport, DistancesArgs, OutputFormat},
+    commands::{fail, load, quiet_on_stdout, save, saved, write_bytes, NO_SOLUTION},
+    log::{info, verbose},
+    node::NodeType,
+    statistics::Statistics,
+};
+
+const DISTANCES_FILENAME: &str = "./distances.png";
+const RAW_FILENAME: &str = "./distances.raw";
+
+pub fn run(args: &DistancesArgs) {
+    let default = match args.export {
+        DistanceExport::Raw => RAW_FILENAME,
+        _ => DISTANCES_FILENAME,
+    };
+    let output = args.output.as_deref().unwrap_or(Path::new(default));
+    quiet_on_stdout(output);
+
+    let mut statistics = Statistics::default();
+    let (image, maze) = load(&args.filename, &mut statistics);
+    let Some(start) = maze.data.get(&NodeType::Start) else {
+        fail("The maze has no entrance");
+    };
+    let field = DistanceField::new(&maze, start.point);
+    verbose!(
+        "reached {} pixels, the farthest is {} pixels from the entrance",
+        field.reached_pixels(),
+        field.max()
+    );
+
+    match args.export {
+        DistanceExport::Color => {
+            let Some(format) = OutputFormat::from_path(output)
+                .unwrap_or(OutputFormat::Png)
+                .raster()
+            else {
+                fail("The colored distances can only be saved as a raster image");
+            };
+            save(&field.colored(&image, args.colormap), output, format);
+        }
+        DistanceExport::Gray16 => {
+            if OutputFormat::from_path(output).is_some_and(|format| format != OutputFormat::Png) {
+                fail("The 16 bit grayscale distances can only be saved as png");
+            }
+            if field.scaled() {
+                eprintln!(
+                    "warning: the maze is too long for 16 bits, the distances are scaled down to fit"
+                );
+            }
+            let bytes = encode_gray16(&field.gray16())
+                .unwrap_or_else(|err| fail(format!("Could not encode the distances: {err}")));
+            write_bytes(output, &bytes);
+        }
+        DistanceExport::Raw => {
+            write_bytes(output, &field.raw());
+            info!(
+                "{}x{} little endian u32 values, {} where there is no distance",
+                field.width,
+                field.height,
+                u32::MAX
+            );
+        }
+    }
+    saved("distances", output);
+
+    // The distance to the exit should match the length of the shortest solution
+    let exit_distance = maze
+        .data
+        .get(&NodeType::Exit)
+        .and_then(|exit| field.at(exit.point));
+    let Some(distance) = exit_distance else {
+        info!("the exit can't be reached from the entrance");
+        exit(NO_SOLUTION);
+    };
+    info!("distance to the exit: {distance} pixels");
+    match Dijkstra.solve(&maze) {
+        Some(solution) if solution.distance() == distance => {}
+        Some(solution) => eprintln!(
+            "warning: the shortest solution is {} pixels long, the distance to the exit {distance}",
+            solution.distance()
+        ),
+        None => eprintln!("warning: the exit has a distance, but no solution reaches it"),
+    }
+}
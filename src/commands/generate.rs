@@ -0,0 +1,60 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    cli::GenerateArgs,
+    colors::PathColor,
+    commands::{fail, quiet_on_stdout, save, saved, write},
+    generator::{generate, GeneratorOptions},
+    log::info,
+    svg,
+};
+
+const MAZE_FILENAME: &str = "./maze.png";
+
+pub fn run(args: &GenerateArgs) {
+    quiet_on_stdout(args.output.output_or(MAZE_FILENAME));
+
+    if !(0.0..=1.0).contains(&args.braid) {
+        fail("--braid has to be between 0.0 and 1.0");
+    }
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    });
+    let options = GeneratorOptions {
+        width: args.width,
+        height: args.height,
+        seed,
+        braid: args.braid,
+    };
+    let image = generate(&options);
+    info!(
+        "generated a {}x{} maze with seed: {seed}",
+        image.image.width(),
+        image.image.height()
+    );
+
+    let output = args.output.output_or(MAZE_FILENAME);
+    if let Some(format) = args.output.format(output).raster() {
+        let options = args.output.render_options();
+        if options.is_identity() {
+            save(&image, output, format);
+        } else {
+            // Upscaled mazes no longer follow the one pixel format, but are nicer to look at
+            let rendered = image
+                .render(None, &PathColor::default(), &options)
+                .unwrap_or_else(|err| fail(err));
+            save(&rendered, output, format);
+        }
+    } else {
+        write(
+            output,
+            &svg::render(&image, None, &args.output.svg_options(PathColor::default()))
+                .unwrap_or_else(|err| fail(err)),
+        );
+    }
+    saved("maze", output);
+}
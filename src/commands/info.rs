@@ -0,0 +1,25 @@
+use crate::{
+    cli::{GlobalArgs, MazeArgs},
+    commands::load,
+    debug,
+    statistics::Statistics,
+};
+
+pub fn run(args: &MazeArgs, global: &GlobalArgs) {
+    let mut statistics = Statistics::default();
+    statistics.general.filename = args.filename.to_str().unwrap();
+    let (_, maze) = load(&args.filename, &mut statistics);
+
+    println!();
+    println!("size: {}x{}", maze.width, maze.height);
+    println!("pixels: {}", statistics.img.total);
+    println!("nodes: {}", maze.data.len());
+    println!("edges: {}", maze.edge_count());
+    for (kind, count) in debug::count_kinds(&maze) {
+        println!("{kind}: {count}");
+    }
+
+    if global.statistics {
+        println!("{:#?}", statistics);
+    }
+}
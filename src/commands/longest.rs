@@ -0,0 +1,95 @@
+use serde::Serialize;
+
+use crate::{
+    algorithms::longest::{with_openings, Corridors, Route},
+    cli::{LongestArgs, OutputFormat, ReportFormat},
+    commands::{fail, open, print_stdout, quiet_on_stdout, save, saved},
+    img::is_stdio,
+    log::{self, info, Level},
+    node::Point,
+};
+
+#[derive(Serialize)]
+struct Report<'a> {
+    perfect: bool,
+    longest: Option<&'a Route>,
+    current: Option<&'a Route>,
+    hardest: Option<&'a Route>,
+}
+
+pub fn run(args: &LongestArgs) {
+    let json = args.format == ReportFormat::Json;
+    let to_stdout = args.rewrite.as_deref().is_some_and(is_stdio);
+    if json && to_stdout {
+        fail("The json report and the maze can't both be written to stdout");
+    }
+    if json {
+        log::set_level(Level::Quiet);
+    }
+    if let Some(rewrite) = &args.rewrite {
+        quiet_on_stdout(rewrite);
+    }
+
+    let image = open(&args.filename);
+    let corridors = Corridors::new(&image);
+    let perfect = corridors.is_perfect();
+    if !perfect {
+        eprintln!(
+            "warning: the maze has loops or parts that can't be reached, the routes may not be the longest"
+        );
+    }
+
+    let longest = corridors.diameter();
+    let current = corridors.current();
+    let hardest = corridors.hardest_openings();
+
+    if json {
+        let report = Report {
+            perfect,
+            longest: longest.as_ref(),
+            current: current.as_ref(),
+            hardest: hardest.as_ref(),
+        };
+        print_stdout(&(serde_json::to_string_pretty(&report).unwrap() + "\n"));
+    } else if !to_stdout {
+        let point = |point: Point| format!("{},{}", point.x, point.y);
+        if let Some(route) = &longest {
+            println!(
+                "longest route: {} pixels from {} to {}",
+                route.length,
+                point(route.from),
+                point(route.to)
+            );
+        }
+        if let Some(route) = &current {
+            println!("current entrance and exit: {} pixels apart", route.length);
+        }
+        if let Some(route) = &hardest {
+            println!(
+                "hardest entrance and exit: {} and {}, {} pixels apart",
+                point(route.from),
+                point(route.to),
+                route.length
+            );
+        }
+    }
+
+    let Some(rewrite) = &args.rewrite else {
+        return;
+    };
+    let Some(hardest) = &hardest else {
+        fail("The maze has no route from the top row to the bottom row");
+    };
+    let Some(format) = OutputFormat::from_path(rewrite)
+        .unwrap_or(OutputFormat::Png)
+        .raster()
+    else {
+        fail("The rewritten maze can only be saved as a raster image");
+    };
+    save(&with_openings(&image, hardest), rewrite, format);
+    info!(
+        "moved the entrance and exit, the maze is now {} pixels through",
+        hardest.length
+    );
+    saved("maze", rewrite);
+}
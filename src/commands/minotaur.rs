@@ -0,0 +1,82 @@
+use std::{process::exit, time::Instant};
+
+use crate::{
+    algorithms::{minotaur::Pursuit, AbortReason, SolveContext},
+    cli::MinotaurArgs,
+    commands::{fail, open, quiet_on_stdout, save, saved, ABORTED, NO_SOLUTION},
+    log::{self, info, verbose},
+    overlay::{self, Layer, PALETTE},
+};
+
+const MINOTAUR_FILENAME: &str = "./minotaur.png";
+// The search keeps every position of the two it has seen, which grows with the square of the maze.
+// Without --max-steps it stops at this many rather than run out of memory.
+const MAX_STATES: u32 = 20_000_000;
+
+pub fn run(args: &MinotaurArgs) {
+    let output = args.output.output_or(MINOTAUR_FILENAME);
+    let Some(format) = args.output.format(output).raster() else {
+        fail("The chase can only be rendered as a raster image");
+    };
+    quiet_on_stdout(output);
+
+    let image = open(&args.filename);
+    let pursuit = Pursuit::new(&image, args.steps);
+    let start = args.minotaur;
+    if !pursuit.open(start) {
+        fail(format!(
+            "The Minotaur has to start on a path, {},{} is a wall or outside the maze",
+            start.x, start.y
+        ));
+    }
+
+    let spinner = log::spinner("running from the Minotaur".into());
+    let started = Instant::now();
+    let max_states = args.limits.max_steps.unwrap_or(MAX_STATES);
+    let context = SolveContext::new(args.limits.timeout, Some(max_states));
+    let result = pursuit.escape(start, &context);
+    log::stop(spinner);
+    let escape = result.unwrap_or_else(|aborted| {
+        eprintln!("looking for a way past the Minotaur {aborted}");
+        if aborted.reason == AbortReason::MaxSteps && args.limits.max_steps.is_none() {
+            eprintln!(
+                "the search stops at {MAX_STATES} positions unless --max-steps says otherwise"
+            );
+        }
+        exit(ABORTED);
+    });
+    let Some(escape) = escape else {
+        eprintln!("The Minotaur catches Theseus whichever way he goes");
+        exit(NO_SOLUTION);
+    };
+    info!(
+        "Theseus escapes in {} turns, {} of them waiting, the Minotaur walks {} pixels",
+        escape.turns,
+        escape.waits,
+        escape.minotaur.len() - 1
+    );
+    verbose!(
+        "looked at {} positions in {:?}",
+        escape.states,
+        started.elapsed()
+    );
+
+    let mut theseus = escape.theseus.clone();
+    theseus.dedup();
+    let layers = [
+        Layer {
+            label: format!("Theseus: {} turns", escape.turns),
+            pixels: theseus,
+            color: PALETTE[1],
+        },
+        Layer {
+            label: "Minotaur".into(),
+            pixels: escape.minotaur,
+            color: PALETTE[0],
+        },
+    ];
+    let rendered = overlay::render(&image, &layers, &args.output.render_options())
+        .unwrap_or_else(|err| fail(err));
+    save(&rendered, output, format);
+    saved("chase", output);
+}
@@ -0,0 +1,174 @@
+use std::{
+    fs,
+    io::{stdout, ErrorKind, Write},
+    path::Path,
+    process::exit,
+    time::Instant,
+};
+
+use image::ImageFormat;
+
+use crate::{
+    img::{is_stdio, Image},
+    log::{self, info, verbose, Level},
+    maze::Maze,
+    statistics::Statistics,
+};
+
+pub mod batch;
+pub mod bench;
+pub mod config;
+pub mod distances;
+pub mod generate;
+pub mod info;
+pub mod longest;
+pub mod minotaur;
+pub mod render;
+pub mod solve;
+pub mod validate;
+pub mod verify;
+pub mod watch;
+
+// Exit codes, so scripts can tell a maze without a solution apart from a failure.
+pub const ERROR: i32 = 1;
+pub const NO_SOLUTION: i32 = 2;
+pub const ABORTED: i32 = 3;
+
+pub const TITLE: &str = "
+
+████████╗██╗  ██╗███████╗███████╗███████╗██╗   ██╗███████╗      ██████╗ ███████╗
+╚══██╔══╝██║  ██║██╔════╝██╔════╝██╔════╝██║   ██║██╔════╝      ██╔══██╗██╔════╝
+   ██║   ███████║█████╗  ███████╗█████╗  ██║   ██║███████╗█████╗██████╔╝███████╗
+   ██║   ██╔══██║██╔══╝  ╚════██║██╔══╝  ██║   ██║╚════██║╚════╝██╔══██╗╚════██║
+   ██║   ██║  ██║███████╗███████║███████╗╚██████╔╝███████║      ██║  ██║███████║
+   ╚═╝   ╚═╝  ╚═╝╚══════╝╚══════╝╚══════╝ ╚═════╝ ╚══════╝      ╚═╝  ╚═╝╚══════╝
+
+";
+
+pub const SOLVED: &str = "
+
+███╗   ███╗ █████╗ ███████╗███████╗    ███████╗ ██████╗ ██╗    ██╗   ██╗███████╗██████╗
+████╗ ████║██╔══██╗╚══███╔╝██╔════╝    ██╔════╝██╔═══██╗██║    ██║   ██║██╔════╝██╔══██╗
+██╔████╔██║███████║  ███╔╝ █████╗      ███████╗██║   ██║██║    ██║   ██║█████╗  ██║  ██║
+██║╚██╔╝██║██╔══██║ ███╔╝  ██╔══╝      ╚════██║██║   ██║██║    ╚██╗ ██╔╝██╔══╝  ██║  ██║
+██║ ╚═╝ ██║██║  ██║███████╗███████╗    ███████║╚██████╔╝███████╗╚████╔╝ ███████╗██████╔╝
+╚═╝     ╚═╝╚═╝  ╚═╝╚══════╝╚══════╝    ╚══════╝ ╚═════╝ ╚══════╝ ╚═══╝  ╚══════╝╚═════╝
+
+";
+
+// Loads and analyzes a maze behind a spinner and a progress bar, filling in the image statistics.
+pub fn load(filename: &Path, statistics: &mut Statistics) -> (Image, Maze) {
+    let spinner = log::spinner(format!("loading image: {}", filename.display()));
+    let start = Instant::now();
+    let image = open(filename);
+    log::stop(spinner);
+    check_format(filename, &image);
+    let progress = log::progress("analyzing maze".into());
+    let maze = Maze::from_image_with_progress(&image, statistics, &progress);
+    let load_duration = start.elapsed();
+    let maze = maze.unwrap_or_else(|err| fail(analysis_error(filename, err)));
+    info!(
+        "loading maze: {} took: {:?}",
+        filename.display(),
+        load_duration
+    );
+    info!("number of nodes loaded: {}", maze.data.len());
+    verbose!(
+        "image: {}x{} pixels, dead ends: {}",
+        statistics.img.width,
+        statistics.img.height,
+        statistics.maze.dead_ends
+    );
+    statistics.img.load_duration = load_duration;
+    (image, maze)
+}
+
+// Fails on images the analyzer can't make sense of, before analyzing them. Problems it can get
+// past are only warned about.
+pub fn check_format(filename: &Path, image: &Image) {
+    if let Err(err) = format_error(image) {
+        fail(format!("{}: {err}", filename.display()));
+    }
+    let problems = validate::format_problems(image);
+    if !problems.is_empty() {
+        eprintln!(
+            "warning: {} doesn't follow the maze format, {}",
+            filename.display(),
+            problems.join(", ")
+        );
+    }
+}
+
+pub fn format_error(image: &Image) -> Result<(), String> {
+    let problems = validate::blocking_problems(image);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "not a valid maze, {}, see `theseus-rs validate`",
+        problems.join(", ")
+    ))
+}
+
+pub fn analysis_error(filename: &Path, err: impl std::fmt::Display) -> String {
+    format!("Could not analyze {}: {err}", filename.display())
+}
+
+pub fn open(filename: &Path) -> Image {
+    Image::open(filename)
+        .unwrap_or_else(|err| fail(format!("Could not open {}: {err}", filename.display())))
+}
+
+// Prints the error and exits, for failures the user has to fix before trying again.
+pub fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    exit(ERROR);
+}
+
+// Anything but the image itself would end up in the output when writing it to stdout.
+pub fn quiet_on_stdout(output: &Path) {
+    if is_stdio(output) {
+        log::set_level(Level::Quiet);
+    }
+}
+
+pub fn save(image: &Image, output: &Path, format: ImageFormat) {
+    image
+        .save(output, format)
+        .unwrap_or_else(|err| fail(format!("Could not save {}: {err}", output.display())));
+}
+
+// Writes text output, like an svg, to a file or to stdout for `-`.
+pub fn write(output: &Path, contents: &str) {
+    write_bytes(output, contents.as_bytes());
+}
+
+pub fn write_bytes(output: &Path, contents: &[u8]) {
+    let result = if is_stdio(output) {
+        stdout().lock().write_all(contents)
+    } else {
+        fs::write(output, contents)
+    };
+    result.unwrap_or_else(|err| fail(format!("Could not save {}: {err}", output.display())));
+}
+
+// Writes a report to stdout. A reader that stops early, like `head`, is no reason to fail.
+pub fn print_stdout(report: &str) {
+    let mut stdout = stdout().lock();
+    let result = stdout
+        .write_all(report.as_bytes())
+        .and_then(|_| stdout.flush());
+    match result {
+        Err(err) if err.kind() != ErrorKind::BrokenPipe => {
+            fail(format!("Could not write to stdout: {err}"))
+        }
+        _ => {}
+    }
+}
+
+pub fn saved(what: &str, output: &Path) {
+    info!(
+        "💾Saved {what} to file: {}",
+        output.file_name().unwrap().to_str().unwrap()
+    );
+}
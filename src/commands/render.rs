@@ -0,0 +1,48 @@
+use crate::{
+    cli::RenderArgs,
+    colors::PathColor,
+    commands::{fail, load, quiet_on_stdout, save, saved, write},
+    debug,
+    log::info,
+    statistics::Statistics,
+    svg,
+};
+
+const RENDER_FILENAME: &str = "./render.png";
+
+pub fn run(args: &RenderArgs) {
+    let output = args.output.output_or(RENDER_FILENAME);
+    let format = args.output.format(output).raster();
+    quiet_on_stdout(output);
+
+    let mut statistics = Statistics::default();
+    let (image, maze) = load(&args.filename, &mut statistics);
+
+    if args.graph {
+        let Some(format) = format else {
+            fail("The node graph can only be rendered as a raster image");
+        };
+        for (kind, count) in debug::count_kinds(&maze) {
+            info!("{kind}: {count}");
+        }
+        let rendered = debug::render(&image, &maze, &args.output.render_options())
+            .unwrap_or_else(|err| fail(err));
+        save(&rendered, output, format);
+        saved("node graph", output);
+        return;
+    }
+
+    if let Some(format) = format {
+        let rendered = image
+            .render(None, &PathColor::default(), &args.output.render_options())
+            .unwrap_or_else(|err| fail(err));
+        save(&rendered, output, format);
+    } else {
+        write(
+            output,
+            &svg::render(&image, None, &args.output.svg_options(PathColor::default()))
+                .unwrap_or_else(|err| fail(err)),
+        );
+    }
+    saved("maze", output);
+}
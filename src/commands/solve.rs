@@ -0,0 +1,426 @@
+use std::{
+    fs,
+    io::{BufWriter, Write},
+    path::Path,
+    process::exit,
+    sync::Arc,
+    time::Instant,
+};
+
+use serde::Serialize;
+
+use crate::{
+    algorithms::{paths, Aborted, Algorithm, Solution, SolveContext},
+    cli::{GlobalArgs, PathPoints, ReportFormat, SolveArgs},
+    commands::{
+        fail, load, print_stdout, quiet_on_stdout, save, saved, write, ABORTED, NO_SOLUTION,
+        SOLVED, TITLE,
+    },
+    img::{is_stdio, Image},
+    log::{self, debug, info, verbose, Level},
+    maze::Maze,
+    node::Point,
+    overlay::{self, Layer},
+    simplify::Pass,
+    statistics::Statistics,
+    svg,
+};
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    statistics: &'a Statistics<'a>,
+    solution: Option<&'a SolutionReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aborted: Option<&'a Aborted>,
+}
+
+#[derive(Debug, Serialize)]
+struct SolutionReport {
+    decisions: u32,
+    expanded: u32,
+    length: usize,
+    path: PathReport,
+}
+
+// Points are ordered from the entrance to the exit, whichever way the solver found them.
+#[derive(Debug, PartialEq, Serialize)]
+struct PathReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nodes: Option<Vec<Point>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pixels: Option<Vec<Point>>,
+}
+
+pub fn run(args: &SolveArgs, global: &GlobalArgs) {
+    let mut statistics = Statistics::default();
+
+    let filename = if let Some(filename) = args.filename.as_deref() {
+        statistics.general.filename = filename.to_str().unwrap();
+        filename
+    } else {
+        fail("No filename was provided");
+    };
+
+    let algorithm = args.algorithm();
+
+    let several = !args.compare.is_empty() || args.paths.is_some() || args.all_paths.is_some();
+    if algorithm.is_none() && !several {
+        fail("No algorithm was defined, please check the help section (--help)");
+    }
+
+    let json = args.format == ReportFormat::Json;
+    if json && several {
+        fail("Comparisons and multiple paths can not be reported as json");
+    }
+
+    if let Some(passes) = &args.simplify {
+        if passes.contains(&Pass::Dedupe) && !passes.contains(&Pass::Collapse) {
+            fail("The dedupe pass removes the parallel edges collapse makes, use it with collapse");
+        }
+    }
+
+    let output = args.output.output();
+    if json && is_stdio(output) {
+        fail("The json report and the solution can't both be written to stdout");
+    }
+    if json {
+        // Anything but the report would break the json on stdout
+        log::set_level(Level::Quiet);
+    }
+    quiet_on_stdout(output);
+
+    log::banner(TITLE);
+
+    let (mut image, mut maze) = load(filename, &mut statistics);
+    let load_duration = statistics.img.load_duration;
+    if let Some(passes) = &args.simplify {
+        let simplified = maze.simplify(passes);
+        info!(
+            "simplifying the maze took: {:?}, {} nodes and {} edges left of {} and {}",
+            simplified.duration,
+            simplified.nodes_after,
+            simplified.edges_after,
+            simplified.nodes_before,
+            simplified.edges_before
+        );
+        statistics.maze.simplified = Some(simplified);
+    }
+
+    if !args.compare.is_empty() {
+        compare(args, &image, &maze);
+        return;
+    }
+    if args.paths.is_some() || args.all_paths.is_some() {
+        several_paths(args, &image, &maze);
+        return;
+    }
+
+    // Checked before loading, without `--compare` or the path searches there is an algorithm
+    let algorithm = algorithm.unwrap();
+    let solution_time = Instant::now();
+
+    info!("Solution algorithm defined: {algorithm}");
+    let context = SolveContext {
+        progress: Some(Arc::new(log::progress("lets solve this bad boy...".into()))),
+        ..args.limits.context()
+    };
+    let result = match &args.trace {
+        Some(trace) => solve_traced(&algorithm, &maze, &context, trace),
+        None => algorithm.solve_within(&maze, &context),
+    };
+
+    let solution_time = solution_time.elapsed();
+    statistics.alg.algorithm = Some(algorithm.clone());
+    statistics.alg.solution_time = solution_time;
+    statistics.general.time_total = solution_time + load_duration;
+
+    let result = result.unwrap_or_else(|aborted| {
+        // Partial statistics, up to the point the solver gave up
+        statistics.alg.decisions = aborted.decisions;
+        if json {
+            print_report(&statistics, None, Some(&aborted));
+        } else {
+            eprintln!("{} {aborted}", algorithm.name());
+            if global.statistics {
+                print_statistics(&statistics, output);
+            }
+        }
+        exit(ABORTED);
+    });
+
+    let Some(solution) = result else {
+        if json {
+            print_report(&statistics, None, None);
+        } else {
+            eprintln!("{} found no solution", algorithm.name());
+        }
+        exit(NO_SOLUTION);
+    };
+
+    statistics.alg.decisions = solution.count;
+    statistics.alg.expanded = solution.expanded;
+    statistics.alg.solution_length = solution.length as u32;
+
+    log::banner(SOLVED);
+    info!("finding the solution took: {:?}", solution_time);
+    info!("number of decisions: {:?}", solution.count);
+    verbose!("nodes expanded: {}", solution.expanded);
+    verbose!("solution length: {} nodes", solution.length);
+
+    if let Some(format) = args.output.format(output).raster() {
+        let options = args.output.render_options();
+        let color = args.colors.path_color();
+        if options.is_identity() {
+            // Paint straight onto the loaded image instead of copying large mazes
+            image.apply_solution(&solution, &color);
+            save(&image, output, format);
+        } else {
+            let rendered = image
+                .render(Some(&solution), &color, &options)
+                .unwrap_or_else(|err| fail(err));
+            save(&rendered, output, format);
+        }
+    } else {
+        let options = args.output.svg_options(args.colors.path_color());
+        let svg = svg::render(&image, Some(&solution), &options).unwrap_or_else(|err| fail(err));
+        write(output, &svg);
+    }
+
+    if let Some(path_output) = &args.path_output {
+        save_path(path_output, &path_report(&solution, args.points));
+        saved("solution path", path_output);
+    }
+
+    if json {
+        print_report(
+            &statistics,
+            Some(&solution_report(&solution, args.points)),
+            None,
+        );
+        return;
+    }
+
+    saved("solution", output);
+
+    if global.statistics {
+        print_statistics(&statistics, output);
+    } else {
+        debug!("{:#?}", statistics);
+    }
+
+    info!("Freeing up memory and exiting program.")
+}
+
+fn path_report(solution: &Solution, points: PathPoints) -> PathReport {
+    PathReport {
+        nodes: (points != PathPoints::Pixels).then(|| solution.points()),
+        pixels: (points != PathPoints::Nodes).then(|| solution.pixels()),
+    }
+}
+
+fn solution_report(solution: &Solution, points: PathPoints) -> SolutionReport {
+    SolutionReport {
+        decisions: solution.count,
+        expanded: solution.expanded,
+        length: solution.length,
+        path: path_report(solution, points),
+    }
+}
+
+// Goes to stderr when the solution is written to stdout, so it doesn't end up after the image.
+fn print_statistics(statistics: &Statistics, output: &Path) {
+    if is_stdio(output) {
+        eprintln!("{:#?}", statistics);
+    } else {
+        println!("{:#?}", statistics);
+    }
+}
+
+fn print_report(
+    statistics: &Statistics,
+    solution: Option<&SolutionReport>,
+    aborted: Option<&Aborted>,
+) {
+    let report = Report {
+        statistics,
+        solution,
+        aborted,
+    };
+    print_stdout(&(serde_json::to_string_pretty(&report).unwrap() + "\n"));
+}
+
+fn save_path(output: &Path, path: &PathReport) {
+    let json = serde_json::to_string(path).unwrap();
+    fs::write(output, json + "\n").unwrap_or_else(|err| fail(err));
+}
+
+// Solves while writing each event of the search as a line of json, written as it happens so the
+// trace of a solver that gives up is still complete up to that point.
+fn solve_traced<'a>(
+    algorithm: &Algorithm,
+    maze: &'a Maze,
+    context: &SolveContext,
+    trace: &Path,
+) -> Result<Option<Solution<'a>>, Aborted> {
+    let file = fs::File::create(trace).unwrap_or_else(|err| fail(err));
+    let mut writer = BufWriter::new(file);
+    let mut failed = None;
+    let result = algorithm.solve_watched(maze, context, |event| {
+        if failed.is_none() {
+            let line = serde_json::to_string(&event).unwrap();
+            failed = writeln!(writer, "{line}").err();
+        }
+    });
+    if let Some(err) = failed.or(writer.flush().err()) {
+        fail(format!("Could not write {}: {err}", trace.display()));
+    }
+    saved("solver trace", trace);
+    result
+}
+
+// Runs every algorithm from `--compare` on the same maze and renders all paths into one image.
+fn compare(args: &SolveArgs, image: &Image, maze: &Maze) {
+    let output = args.output.output();
+    let Some(format) = args.output.format(output).raster() else {
+        fail("Comparisons can only be rendered as raster images");
+    };
+
+    let mut layers = Vec::new();
+    for (algorithm, color) in args.compare.iter().zip(overlay::colors(args.compare.len())) {
+        let context = SolveContext {
+            progress: Some(Arc::new(log::progress(format!("solving with {algorithm}")))),
+            ..args.limits.context()
+        };
+        let solution_time = Instant::now();
+        let result = algorithm.solve_within(maze, &context);
+        let solution_time = solution_time.elapsed();
+
+        let solution = match result {
+            Ok(Some(solution)) => solution,
+            Ok(None) => {
+                info!("{algorithm} found no solution");
+                continue;
+            }
+            Err(aborted) => {
+                info!("{algorithm} {aborted}");
+                continue;
+            }
+        };
+        info!(
+            "{algorithm} took: {:?}, decisions: {}, expanded: {}, solution length: {}",
+            solution_time, solution.count, solution.expanded, solution.length
+        );
+        layers.push(Layer {
+            label: algorithm.name().into(),
+            pixels: solution.pixels(),
+            color,
+        });
+    }
+
+    if layers.is_empty() {
+        eprintln!("None of the algorithms found a solution");
+        exit(NO_SOLUTION);
+    }
+
+    let rendered = overlay::render(image, &layers, &args.output.render_options())
+        .unwrap_or_else(|err| fail(err));
+    save(&rendered, output, format);
+    saved("comparison", output);
+}
+
+// Finds the K shortest paths with `--paths`, or every path up to a length with `--all-paths`, and
+// renders them all into one image.
+fn several_paths(args: &SolveArgs, image: &Image, maze: &Maze) {
+    let output = args.output.output();
+    let Some(format) = args.output.format(output).raster() else {
+        fail("Multiple paths can only be rendered as raster images");
+    };
+
+    let context = args.limits.context();
+    let spinner = log::spinner("looking for paths".into());
+    let solution_time = Instant::now();
+    let result = match (args.paths, args.all_paths) {
+        (Some(k), _) => paths::k_shortest(maze, k as usize, &context),
+        (None, Some(max_length)) => {
+            paths::all_simple(maze, max_length, args.max_paths as usize, &context)
+        }
+        (None, None) => unreachable!("only called with --paths or --all-paths"),
+    };
+    log::stop(spinner);
+    let solutions = result.unwrap_or_else(|aborted| {
+        eprintln!("looking for paths {aborted}");
+        exit(ABORTED);
+    });
+    if solutions.is_empty() {
+        eprintln!("No path leads to the exit");
+        exit(NO_SOLUTION);
+    }
+    info!(
+        "found {} paths in {:?}",
+        solutions.len(),
+        solution_time.elapsed()
+    );
+    if args.all_paths.is_some() && solutions.len() == args.max_paths as usize {
+        eprintln!(
+            "warning: stopped looking after {} paths, there may be more, see --max-paths",
+            args.max_paths
+        );
+    }
+
+    let mut layers = Vec::new();
+    for (i, (solution, color)) in solutions
+        .iter()
+        .zip(overlay::colors(solutions.len()))
+        .enumerate()
+    {
+        info!(
+            "path {}: {} pixels, {} nodes",
+            i + 1,
+            solution.distance(),
+            solution.length
+        );
+        layers.push(Layer {
+            label: format!("path {}: {} px", i + 1, solution.distance()),
+            pixels: solution.pixels(),
+            color,
+        });
+    }
+
+    let rendered = overlay::render(image, &layers, &args.output.render_options())
+        .unwrap_or_else(|err| fail(err));
+    save(&rendered, output, format);
+    saved("paths", output);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::{breadth_first::BreadthFirst, Solver};
+    use crate::test_utils::maze_image;
+
+    #[test]
+    fn solution_path_as_json() {
+        let img = maze_image!([[0, 1, 0, 0, 0], [0, 1, 1, 1, 0], [0, 0, 0, 1, 0]]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = BreadthFirst.solve(&maze).unwrap();
+
+        let path = path_report(&solution, PathPoints::Nodes);
+        assert_eq!(None, path.pixels);
+        assert_eq!(
+            r#"{"nodes":[{"x":1,"y":0},{"x":1,"y":1},{"x":3,"y":1},{"x":3,"y":2}]}"#,
+            serde_json::to_string(&path).unwrap()
+        );
+
+        let path = path_report(&solution, PathPoints::Both);
+        assert_eq!(
+            Some(vec![
+                Point::at(1, 0),
+                Point::at(1, 1),
+                Point::at(2, 1),
+                Point::at(3, 1),
+                Point::at(3, 2)
+            ]),
+            path.pixels
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::process::exit;
+
+use image::Pixel;
+
+use crate::{
+    algorithms::{breadth_first::BreadthFirst, Solver},
+    cli::MazeArgs,
+    commands::{open, ERROR},
+    img::Image,
+    log::info,
+    maze::{Maze, PATHWAY, WALL},
+    statistics::Statistics,
+};
+
+pub fn run(args: &MazeArgs) {
+    let image = open(&args.filename);
+    let problems = problems(&image);
+
+    if problems.is_empty() {
+        info!("✅ {} is a valid maze", args.filename.display());
+        return;
+    }
+
+    println!("❌ {} is not a valid maze:", args.filename.display());
+    for problem in problems {
+        println!("  - {problem}");
+    }
+    exit(ERROR);
+}
+
+// Everything that keeps the image from being solved, empty if it follows the maze format.
+pub(crate) fn problems(image: &Image) -> Vec<String> {
+    let mut problems = format_problems(image);
+
+    // The analyzer expects the format to hold, only try solving once it does
+    if problems.is_empty() {
+        let maze = Maze::from_image(image, &mut Statistics::default());
+        let solution = maze.as_ref().ok().and_then(|maze| BreadthFirst.solve(maze));
+        let reachable = solution.is_some_and(|s| s.path.back().is_some_and(|node| node.start));
+        if !reachable {
+            problems.push("there is no path from the entrance to the exit".into());
+        }
+    }
+    problems
+}
+
+// Problems with the format alone. A maze without a way to the exit still follows the format.
+pub(crate) fn format_problems(image: &Image) -> Vec<String> {
+    checks(image)
+        .into_iter()
+        .map(|(_, problem)| problem)
+        .collect()
+}
+
+// The problems the analyzer can't get past, it would fail or build a maze without an entrance or
+// exit. The others only make the maze look wrong.
+pub(crate) fn blocking_problems(image: &Image) -> Vec<String> {
+    checks(image)
+        .into_iter()
+        .filter(|(blocking, _)| *blocking)
+        .map(|(_, problem)| problem)
+        .collect()
+}
+
+// Every format problem, and whether it blocks the analyzer.
+fn checks(image: &Image) -> Vec<(bool, String)> {
+    let image_buffer = &image.image;
+    let (width, height) = image_buffer.dimensions();
+    let mut problems = Vec::new();
+
+    if width < 3 || height < 2 {
+        problems.push((
+            true,
+            format!("the maze is {width}x{height} pixels, it needs to be at least 3x2"),
+        ));
+        return problems;
+    }
+
+    let other_colors: Vec<(u32, u32)> = image_buffer
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel.channels() != WALL && pixel.channels() != PATHWAY)
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    if let Some((x, y)) = other_colors.first() {
+        problems.push((
+            false,
+            format!(
+                "{} pixels are neither black walls nor white paths, the first at {x},{y}",
+                other_colors.len()
+            ),
+        ));
+    }
+
+    let is_path = |x: u32, y: u32| image_buffer.get_pixel(x, y).channels() == PATHWAY;
+
+    let entrances = (0..width).filter(|x| is_path(*x, 0)).count();
+    if entrances != 1 {
+        problems.push((
+            true,
+            format!("the top row has {entrances} openings, there has to be exactly one entrance"),
+        ));
+    }
+    let exits = (0..width).filter(|x| is_path(*x, height - 1)).count();
+    if exits != 1 {
+        problems.push((
+            true,
+            format!("the bottom row has {exits} openings, there has to be exactly one exit"),
+        ));
+    }
+    // The analyzer reaches the exit from the path right above it
+    let exit = (0..width).find(|x| is_path(*x, height - 1));
+    if let Some(x) = exit.filter(|x| height > 2 && !is_path(*x, height - 2)) {
+        problems.push((
+            true,
+            format!("the exit at {x},{} has no path above it", height - 1),
+        ));
+    }
+    if (0..height).any(|y| is_path(0, y) || is_path(width - 1, y)) {
+        problems.push((false, "the left and right borders have to be walls".into()));
+    }
+
+    let wide = (0..height - 1)
+        .flat_map(|y| (0..width - 1).map(move |x| (x, y)))
+        .filter(|(x, y)| {
+            is_path(*x, *y) && is_path(x + 1, *y) && is_path(*x, y + 1) && is_path(x + 1, y + 1)
+        })
+        .count();
+    if wide > 0 {
+        problems.push((
+            false,
+            format!("found {wide} open 2x2 areas, paths have to be exactly one pixel wide"),
+        ));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::maze_image;
+    use image::Rgb;
+
+    #[test]
+    fn valid_maze() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 0, 0]
+        ]);
+        assert!(problems(&img).is_empty());
+    }
+
+    #[test]
+    fn blocked_maze() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 1, 0]
+        ]);
+        assert_eq!(
+            vec!["there is no path from the entrance to the exit".to_string()],
+            problems(&img)
+        );
+    }
+
+    #[test]
+    fn broken_borders() {
+        let mut img = maze_image!([
+            [0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 1],
+            [0, 1, 1, 1, 0],
+            [0, 0, 0, 0, 0]
+        ]);
+        img.image.put_pixel(2, 3, Rgb([255, 0, 0]));
+
+        let problems = problems(&img);
+        assert_eq!(5, problems.len());
+        assert!(problems[0].starts_with("1 pixels are neither"));
+        assert!(problems[1].starts_with("the top row has 2 openings"));
+        assert!(problems[2].starts_with("the bottom row has 0 openings"));
+        assert!(problems[3].starts_with("the left and right borders"));
+        assert!(problems[4].starts_with("found 2 open 2x2 areas"));
+    }
+
+    #[test]
+    fn exit_without_path_above() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 0],
+            [0, 0, 0, 1, 0]
+        ]);
+        assert_eq!(
+            vec!["the exit at 3,4 has no path above it".to_string()],
+            problems(&img)
+        );
+        assert!(Maze::from_image(&img, &mut Statistics::default()).is_err());
+    }
+}
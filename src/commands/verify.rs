@@ -0,0 +1,344 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::{stdin, Read},
+    path::Path,
+    process::exit,
+};
+
+use image::{load_from_memory, Pixel};
+use serde_json::Value;
+
+use crate::{
+    cli::VerifyArgs,
+    commands::{fail, open, validate, ERROR},
+    img::{is_stdio, line, Image},
+    log::info,
+    maze::PATHWAY,
+    node::Point,
+};
+
+const NEIGHBOURS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub fn run(args: &VerifyArgs) {
+    let maze = open(&args.maze);
+    if !validate::problems(&maze).is_empty() {
+        fail(format!(
+            "{} is not a valid maze, see `theseus-rs validate`",
+            args.maze.display()
+        ));
+    }
+
+    let route = read(&args.solution)
+        .and_then(|candidate| match candidate {
+            Candidate::Points(points) => route_from_points(&points),
+            Candidate::Image(image) => route_from_image(&maze, &image),
+        })
+        .and_then(|route| check(&maze, &route).map(|_| route));
+    let route = route.unwrap_or_else(|problem| {
+        println!(
+            "❌ {} is not a solution: {problem}",
+            args.solution.display()
+        );
+        exit(ERROR);
+    });
+
+    println!(
+        "✅ {} is a solution, {} pixels long",
+        args.solution.display(),
+        route.len()
+    );
+    // A valid route means the pixel search finds one as well, and none is shorter than its route
+    let Some(shortest) = shortest(&maze) else {
+        return;
+    };
+    if route.len() == shortest {
+        println!("🏆 it is the shortest solution");
+    } else {
+        println!(
+            "it is {} pixels longer than the shortest solution of {shortest} pixels",
+            route.len() - shortest
+        );
+    }
+}
+
+enum Candidate {
+    Points(Vec<Point>),
+    Image(Image),
+}
+
+// Json is recognized by its first character, anything else has to be an image.
+fn read(path: &Path) -> Result<Candidate, String> {
+    let mut bytes = Vec::new();
+    let result = if is_stdio(path) {
+        stdin().read_to_end(&mut bytes).map(|_| ())
+    } else {
+        fs::read(path).map(|read| bytes = read)
+    };
+    result.map_err(|err| format!("could not read it: {err}"))?;
+
+    let first = bytes.iter().find(|byte| !byte.is_ascii_whitespace());
+    if matches!(first, Some(b'{') | Some(b'[')) {
+        let json: Value = serde_json::from_slice(&bytes).map_err(|err| err.to_string())?;
+        return points(&json).map(Candidate::Points);
+    }
+    let image = load_from_memory(&bytes).map_err(|err| err.to_string())?;
+    Ok(Candidate::Image(Image {
+        image: image.into_rgb8(),
+    }))
+}
+
+// Accepts a list of points, written as `{"x": 1, "y": 2}` or `[1, 2]`, on its own or as written by
+// `--path-output` and `--format json`.
+fn points(json: &Value) -> Result<Vec<Point>, String> {
+    let list = match json {
+        Value::Array(list) => list,
+        Value::Object(object) => {
+            let path = object
+                .get("solution")
+                .and_then(|solution| solution.get("path"))
+                .unwrap_or(json);
+            return match path.get("pixels").or(path.get("nodes")) {
+                Some(list @ Value::Array(_)) => points(list),
+                _ => Err("the json has no list of points".into()),
+            };
+        }
+        _ => return Err("the json has no list of points".into()),
+    };
+
+    let coordinate = |value: Option<&Value>| value.and_then(Value::as_u64).map(|v| v as u32);
+    list.iter()
+        .map(|point| {
+            let (x, y) = match point {
+                Value::Array(pair) if pair.len() == 2 => {
+                    (coordinate(pair.first()), coordinate(pair.get(1)))
+                }
+                point => (coordinate(point.get("x")), coordinate(point.get("y"))),
+            };
+            x.zip(y)
+                .map(|(x, y)| Point::at(x, y))
+                .ok_or(format!("{point} is not a point"))
+        })
+        .collect()
+}
+
+// Fills in the pixels between the points, which have to be in a straight line to connect.
+fn route_from_points(points: &[Point]) -> Result<Vec<Point>, String> {
+    let Some(first) = points.first() else {
+        return Err("the list of points is empty".into());
+    };
+    let mut route = vec![*first];
+    for pair in points.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if from == to {
+            continue;
+        }
+        if from.x != to.x && from.y != to.y {
+            return Err(format!(
+                "it jumps from {},{} to {},{}",
+                from.x, from.y, to.x, to.y
+            ));
+        }
+        route.extend(line(&from, &to).into_iter().skip(1));
+    }
+    Ok(route)
+}
+
+// Every pixel that differs from the maze counts as painted, the route is the shortest way through
+// the painted pixels from the entrance to the exit.
+fn route_from_image(maze: &Image, solved: &Image) -> Result<Vec<Point>, String> {
+    if maze.image.dimensions() != solved.image.dimensions() {
+        let (width, height) = solved.image.dimensions();
+        return Err(format!(
+            "the image is {width}x{height}, the maze is {}x{}",
+            maze.image.width(),
+            maze.image.height()
+        ));
+    }
+
+    let painted: HashSet<Point> = maze
+        .image
+        .enumerate_pixels()
+        .filter(|(x, y, pixel)| solved.image.get_pixel(*x, *y) != *pixel)
+        .map(|(x, y, _)| Point::at(x, y))
+        .collect();
+    if painted.is_empty() {
+        return Err("nothing is painted on the maze".into());
+    }
+
+    let (entrance, exit) = ends(maze);
+    for (end, name) in [(entrance, "entrance"), (exit, "exit")] {
+        if !painted.contains(&end) {
+            return Err(format!("the {name} at {},{} isn't painted", end.x, end.y));
+        }
+    }
+    let route = search(entrance, exit, |point| painted.contains(point));
+    let route = route.ok_or("the painted path isn't continuous")?;
+
+    let off_route = painted.len() - route.len();
+    if off_route > 0 {
+        info!("{off_route} painted pixels are not part of the route");
+    }
+    Ok(route)
+}
+
+fn check(maze: &Image, route: &[Point]) -> Result<(), String> {
+    let (width, height) = maze.image.dimensions();
+    let is_path = |point: &Point| {
+        point.x < width
+            && point.y < height
+            && maze.image.get_pixel(point.x, point.y).channels() == PATHWAY
+    };
+    if let Some(point) = route.iter().find(|point| !is_path(point)) {
+        return Err(format!("it goes through a wall at {},{}", point.x, point.y));
+    }
+
+    let (entrance, exit) = ends(maze);
+    let (first, last) = (route[0], route[route.len() - 1]);
+    if first != entrance {
+        return Err(format!(
+            "it starts at {},{} instead of the entrance at {},{}",
+            first.x, first.y, entrance.x, entrance.y
+        ));
+    }
+    if last != exit {
+        return Err(format!(
+            "it ends at {},{} instead of the exit at {},{}",
+            last.x, last.y, exit.x, exit.y
+        ));
+    }
+    Ok(())
+}
+
+// The entrance and exit, the openings in the top and bottom row of a valid maze.
+fn ends(maze: &Image) -> (Point, Point) {
+    let (width, height) = maze.image.dimensions();
+    let opening = |y: u32| {
+        let x = (0..width)
+            .find(|x| maze.image.get_pixel(*x, y).channels() == PATHWAY)
+            .unwrap();
+        Point::at(x, y)
+    };
+    (opening(0), opening(height - 1))
+}
+
+// Length in pixels of the shortest route from the entrance to the exit.
+fn shortest(maze: &Image) -> Option<usize> {
+    let (width, height) = maze.image.dimensions();
+    let is_path = |point: &Point| {
+        point.x < width
+            && point.y < height
+            && maze.image.get_pixel(point.x, point.y).channels() == PATHWAY
+    };
+    let (entrance, exit) = ends(maze);
+    search(entrance, exit, is_path).map(|route| route.len())
+}
+
+// Breadth first search over pixels, giving the shortest route between two pixels.
+fn search(from: Point, to: Point, open: impl Fn(&Point) -> bool) -> Option<Vec<Point>> {
+    let mut previous: HashMap<Point, Point> = HashMap::from([(from, from)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            break;
+        }
+        for (dx, dy) in NEIGHBOURS {
+            let (x, y) = (current.x as i64 + dx, current.y as i64 + dy);
+            if x < 0 || y < 0 {
+                continue;
+            }
+            let next = Point::at(x as u32, y as u32);
+            if open(&next) && !previous.contains_key(&next) {
+                previous.insert(next, current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    previous.get(&to)?;
+    let mut route = vec![to];
+    while route[route.len() - 1] != from {
+        route.push(previous[&route[route.len() - 1]]);
+    }
+    route.reverse();
+    Some(route)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{maze_image, PATH};
+    use image::Rgb;
+
+    fn maze() -> Image {
+        maze_image!([
+            [0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 0, 0, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ])
+    }
+
+    #[test]
+    fn points_solution() {
+        let maze = maze();
+        let json = serde_json::json!({"nodes": [[1, 0], [1, 3], [3, 3], [3, 5]]});
+        let route = route_from_points(&points(&json).unwrap()).unwrap();
+        assert_eq!(Ok(()), check(&maze, &route));
+        assert_eq!(8, route.len());
+        assert_eq!(Some(8), shortest(&maze));
+
+        let json = serde_json::json!([{"x": 1, "y": 0}, {"x": 1, "y": 3}, {"x": 3, "y": 4}]);
+        assert_eq!(
+            Err("it jumps from 1,3 to 3,4".to_string()),
+            route_from_points(&points(&json).unwrap())
+        );
+
+        let route = route_from_points(&[Point::at(1, 0), Point::at(1, 1), Point::at(3, 1)]);
+        assert_eq!(
+            Err("it ends at 3,1 instead of the exit at 3,5".to_string()),
+            check(&maze, &route.unwrap())
+        );
+    }
+
+    #[test]
+    fn painted_solution() {
+        let maze = maze();
+        let mut solved = maze_image!([
+            [0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 1, 1, 0, 1, 0],
+            [0, 0, 0, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let red = Rgb([255, 0, 0]);
+        // The long way around
+        for (x, y) in [
+            (1, 0),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (4, 1),
+            (5, 1),
+            (5, 2),
+            (5, 3),
+        ] {
+            solved.image.put_pixel(x, y, red);
+        }
+        for (x, y) in [(5, 4), (4, 4), (3, 4), (3, 5)] {
+            solved.image.put_pixel(x, y, red);
+        }
+        let route = route_from_image(&maze, &solved).unwrap();
+        assert_eq!(Ok(()), check(&maze, &route));
+        assert_eq!(12, route.len());
+
+        solved.image.put_pixel(5, 3, PATH);
+        assert_eq!(
+            Err("the painted path isn't continuous".to_string()),
+            route_from_image(&maze, &solved)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    process::exit,
+    time::{Duration, Instant},
+};
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::{
+    cli::WatchArgs,
+    commands::{fail, load, NO_SOLUTION},
+    log::info,
+    statistics::Statistics,
+    tui::{App, State},
+};
+
+// About 30 frames a second, fast enough to look smooth without drawing more than a terminal shows.
+const FRAME: Duration = Duration::from_millis(33);
+
+pub fn run(args: &WatchArgs) {
+    let mut statistics = Statistics::default();
+    statistics.general.filename = args.filename.to_str().unwrap();
+    let (image, maze) = load(&args.filename, &mut statistics);
+
+    let mut app = App::new(&image, &maze, args.algorithm.clone(), args.speed);
+    let mut terminal = ratatui::init();
+    let result = (|| -> std::io::Result<()> {
+        let mut next_frame = Instant::now();
+        while !app.quit {
+            terminal.draw(|frame| app.draw(frame))?;
+            next_frame += FRAME;
+            // Keys are handled as they come, the solver only moves on once a frame
+            while let Some(timeout) = next_frame.checked_duration_since(Instant::now()) {
+                if !event::poll(timeout)? {
+                    break;
+                }
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        app.handle(key.code);
+                    }
+                }
+            }
+            next_frame = next_frame.max(Instant::now());
+            app.tick();
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    if let Err(err) = result {
+        fail(format!("terminal error: {err}"));
+    }
+
+    match app.state() {
+        State::Solved(length) => info!(
+            "{} solved the maze in {} decisions, the path is {length} pixels long",
+            args.algorithm,
+            app.decisions()
+        ),
+        State::NoSolution => {
+            info!("{} found no solution", args.algorithm);
+            exit(NO_SOLUTION);
+        }
+        State::Running | State::Paused => info!(
+            "stopped {} after {} decisions",
+            args.algorithm,
+            app.decisions()
+        ),
+    }
+}
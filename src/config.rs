@@ -0,0 +1,323 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Command;
+use toml::{Table, Value};
+
+pub const FILENAME: &str = "theseus.toml";
+
+// The `[palette]` table sets the colors of every command that draws a maze.
+const PALETTE: [(&str, &str); 3] = [
+    ("wall", "wall-color"),
+    ("path", "path-color"),
+    ("solution", "color"),
+];
+
+// Settings from `theseus.toml` files. Top level keys are defaults for the options of every command,
+// tables named after a command only for that command, e.g.
+//
+//     algorithm = "a-star"
+//     stats = true
+//
+//     [palette]
+//     wall = "#202020"
+//
+//     [batch]
+//     template = "{stem}.png"
+#[derive(Debug, Default)]
+pub struct Config {
+    pub files: Vec<PathBuf>,
+    settings: Table,
+}
+
+impl Config {
+    // Reads the user config, then the one in the working directory and last the one given with
+    // `--config`, where later files override the earlier ones.
+    pub fn load(explicit: Option<PathBuf>) -> Result<Config, String> {
+        let mut config = Config::default();
+        let candidates = [user_path(), Some(PathBuf::from(FILENAME))];
+        for path in candidates.into_iter().flatten() {
+            if path.is_file() {
+                config.read(&path)?;
+            }
+        }
+        if let Some(path) = explicit {
+            config.read(&path)?;
+        }
+        Ok(config)
+    }
+
+    fn read(&mut self, path: &Path) -> Result<(), String> {
+        let settings = fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {err}", path.display()))?
+            .parse::<Table>()
+            .map_err(|err| format!("Could not parse {}: {err}", path.display()))?;
+        merge(&mut self.settings, settings);
+        self.files.push(path.to_path_buf());
+        Ok(())
+    }
+
+    // Turns the settings into default values of the command line options, so flags given on the
+    // command line still take precedence.
+    pub fn apply(&self, mut command: Command) -> Result<Command, String> {
+        let names: Vec<String> = command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect();
+        let shared = self.shared(&command)?;
+
+        // Solving without a subcommand takes the `[solve]` settings as well
+        let solve = self.section(&command, "solve")?;
+        command = defaults(command, shared.iter().chain(&solve));
+        for name in names {
+            let section = self.section(&command, &name)?;
+            command = command.mut_subcommand(&name, |subcommand| {
+                defaults(subcommand, shared.iter().chain(&section))
+            });
+        }
+
+        for (key, value) in &self.settings {
+            if value.is_table() && key != "palette" && command.find_subcommand(key).is_none() {
+                return Err(format!("Unknown section [{key}] in {FILENAME}"));
+            }
+        }
+        Ok(command)
+    }
+
+    // The palette and top level settings, which apply to every command.
+    fn shared(&self, command: &Command) -> Result<Vec<(String, Vec<String>)>, String> {
+        let mut settings = Vec::new();
+        if let Some(palette) = self.settings.get("palette") {
+            let palette = palette
+                .as_table()
+                .ok_or(format!("palette in {FILENAME} has to be a table"))?;
+            for (key, value) in palette {
+                let Some((_, option)) = PALETTE.iter().find(|(name, _)| name == key) else {
+                    return Err(format!("Unknown palette color `{key}` in {FILENAME}"));
+                };
+                settings.push((option.to_string(), values(key, value)?));
+            }
+        }
+
+        for (key, value) in &self.settings {
+            if value.is_table() {
+                continue;
+            }
+            let known = has_option(command, key)
+                || command
+                    .get_subcommands()
+                    .any(|subcommand| has_option(subcommand, key));
+            if !known {
+                return Err(format!("Unknown setting `{key}` in {FILENAME}"));
+            }
+            settings.push((key.clone(), values(key, value)?));
+        }
+        Ok(settings)
+    }
+
+    fn section(&self, command: &Command, name: &str) -> Result<Vec<(String, Vec<String>)>, String> {
+        let Some(section) = self.settings.get(name).and_then(Value::as_table) else {
+            return Ok(Vec::new());
+        };
+        let subcommand = command.find_subcommand(name);
+        let mut settings = Vec::new();
+        for (key, value) in section {
+            if !subcommand.is_some_and(|subcommand| has_option(subcommand, key)) {
+                return Err(format!("Unknown setting `{key}` in [{name}] of {FILENAME}"));
+            }
+            settings.push((key.clone(), values(key, value)?));
+        }
+        Ok(settings)
+    }
+}
+
+// `--config` has to be known before the command line is parsed, as the config decides the defaults.
+pub fn explicit_path(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+fn user_path() -> Option<PathBuf> {
+    let directory = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(directory.join("theseus").join(FILENAME))
+}
+
+fn has_option(command: &Command, name: &str) -> bool {
+    command
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some(name))
+}
+
+fn defaults<'a>(
+    mut command: Command,
+    settings: impl Iterator<Item = &'a (String, Vec<String>)>,
+) -> Command {
+    for (key, values) in settings {
+        let id = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(key.as_str()))
+            .map(|arg| arg.get_id().clone());
+        if let Some(id) = id {
+            command = command.mut_arg(id, |arg| arg.default_values(values.clone()));
+        }
+    }
+    command
+}
+
+// Tables are merged key by key, anything else is replaced.
+fn merge(base: &mut Table, other: Table) {
+    for (key, value) in other {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(other)) => merge(base, other),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// Settings are handed to clap as the strings they would be on the command line.
+fn values(key: &str, value: &Value) -> Result<Vec<String>, String> {
+    let scalar = |value: &Value| match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Integer(value) => Ok(value.to_string()),
+        Value::Float(value) => Ok(value.to_string()),
+        Value::Boolean(value) => Ok(value.to_string()),
+        _ => Err(format!("`{key}` in {FILENAME} has an unsupported value")),
+    };
+    match value {
+        Value::Array(items) => items.iter().map(scalar).collect(),
+        value => scalar(value).map(|value| vec![value]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::Cli;
+    use crate::colors::{Colormap, PathColor};
+    use clap::CommandFactory;
+
+    fn config(settings: &str) -> Config {
+        Config {
+            files: Vec::new(),
+            settings: settings.parse().unwrap(),
+        }
+    }
+
+    fn parse(config: &Config, args: &[&str]) -> Cli {
+        let command = config.apply(Cli::command()).unwrap();
+        Cli::from_matches(&command.try_get_matches_from(args).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn settings_are_defaults() {
+        let config = config(
+            r##"
+            algorithm = "a-star"
+            stats = true
+            scale = 3
+
+            [palette]
+            wall = "#000080"
+
+            [solve]
+            gradient = ["red", "blue"]
+
+            [generate]
+            scale = 5
+            "##,
+        );
+
+        let cli = parse(&config, &["theseus-rs", "solve", "maze.png"]);
+        let Some(crate::cli::Command::Solve(args)) = &cli.command else {
+            panic!("expected the solve command");
+        };
+        assert!(cli.global.statistics);
+        assert_eq!(Some("a-star"), args.algorithm.as_ref().map(|a| a.name()));
+        assert_eq!(3, args.output.scale);
+        assert_eq!(Some(image::Rgb([0, 0, 128])), args.output.wall_color);
+        assert_eq!(
+            Some(vec![image::Rgb([255, 0, 0]), image::Rgb([0, 0, 255])]),
+            args.colors.gradient
+        );
+
+        // Flags on the command line win
+        let cli = parse(&config, &["theseus-rs", "generate", "--scale", "2"]);
+        let Some(crate::cli::Command::Generate(args)) = &cli.command else {
+            panic!("expected the generate command");
+        };
+        assert_eq!(2, args.output.scale);
+    }
+
+    #[test]
+    fn command_line_color_beats_config_colormap() {
+        let config = config("[solve]\ncolormap = \"viridis\"");
+        let blue = image::Rgb([0, 0, 255]);
+
+        let cli = parse(
+            &config,
+            &["theseus-rs", "solve", "maze.png", "--color", "blue"],
+        );
+        let Some(crate::cli::Command::Solve(args)) = &cli.command else {
+            panic!("expected the solve command");
+        };
+        assert_eq!(PathColor::Solid(blue), args.colors.path_color());
+
+        let cli = parse(&config, &["theseus-rs", "maze.png", "--color", "blue"]);
+        assert_eq!(PathColor::Solid(blue), cli.solve.colors.path_color());
+
+        let cli = parse(&config, &["theseus-rs", "solve", "maze.png"]);
+        let Some(crate::cli::Command::Solve(args)) = &cli.command else {
+            panic!("expected the solve command");
+        };
+        assert_eq!(
+            PathColor::Colormap(Colormap::Viridis),
+            args.colors.path_color()
+        );
+    }
+
+    #[test]
+    fn unknown_settings() {
+        assert!(config("colour = \"red\"").apply(Cli::command()).is_err());
+        assert!(config("[solve]\ntemplate = \"x\"")
+            .apply(Cli::command())
+            .is_err());
+        assert!(config("[palette]\nfloor = \"red\"")
+            .apply(Cli::command())
+            .is_err());
+    }
+
+    #[test]
+    fn merged_tables() {
+        let mut base: Table = "a = 1\n[solve]\nscale = 2\ncolor = \"red\""
+            .parse()
+            .unwrap();
+        merge(&mut base, "[solve]\nscale = 4".parse().unwrap());
+        assert_eq!(Some(4), base["solve"]["scale"].as_integer());
+        assert_eq!(Some("red"), base["solve"]["color"].as_str());
+        assert_eq!(Some(1), base["a"].as_integer());
+    }
+
+    #[test]
+    fn config_flag() {
+        let args = ["theseus-rs", "solve", "--config=a.toml"].map(OsString::from);
+        assert_eq!(Some(PathBuf::from("a.toml")), explicit_path(args));
+        let args = ["theseus-rs", "--config", "b.toml", "info"].map(OsString::from);
+        assert_eq!(Some(PathBuf::from("b.toml")), explicit_path(args));
+    }
+}
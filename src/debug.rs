@@ -0,0 +1,125 @@
+use fxhash::FxHashMap;
+use image::Rgb;
+
+use crate::{
+    colors::PathColor,
+    img::{fill_block, line, paint_segments, Image, RenderOptions},
+    maze::Maze,
+    node::NodeKind,
+    overlay::with_legend,
+};
+
+pub const EDGE: Rgb<u8> = Rgb([120, 200, 255]);
+
+pub const KINDS: [(NodeKind, &str, Rgb<u8>); 6] = [
+    (NodeKind::Start, "start", Rgb([0, 200, 0])),
+    (NodeKind::Exit, "exit", Rgb([220, 0, 0])),
+    (NodeKind::Junction, "junction", Rgb([0, 70, 255])),
+    (NodeKind::Corner, "corner", Rgb([255, 160, 0])),
+    (NodeKind::Corridor, "corridor", Rgb([150, 0, 200])),
+    (NodeKind::DeadEnd, "dead end", Rgb([255, 0, 150])),
+];
+
+fn kind_color(kind: NodeKind) -> Rgb<u8> {
+    KINDS.iter().find(|(k, _, _)| *k == kind).unwrap().2
+}
+
+// Renders the analyzed node graph on top of the maze, every edge between two nodes as a line and
+// every node colored by its kind, so the analyzer can be verified visually.
+pub fn render(image: &Image, maze: &Maze, options: &RenderOptions) -> Result<Image, String> {
+    let mut canvas = image.render(None, &PathColor::default(), options)?.image;
+    let (width, height) = image.image.dimensions();
+    let region = options.region(width, height);
+    let scale = options.scale.max(1);
+    let line_width = options
+        .line_width
+        .unwrap_or(scale.div_ceil(3))
+        .clamp(1, scale);
+
+    for node in maze.data.values() {
+        for child in node.children.values() {
+            let edge = line(&node.point, child);
+            paint_segments(
+                &mut canvas,
+                &edge,
+                |_| Some(EDGE),
+                &region,
+                scale,
+                line_width,
+            );
+        }
+    }
+
+    for node in maze.data.values() {
+        fill_block(
+            &mut canvas,
+            &node.point,
+            &region,
+            scale,
+            kind_color(node.kind()),
+        );
+    }
+
+    let entries: Vec<(&str, Rgb<u8>)> = KINDS
+        .iter()
+        .map(|(_, label, color)| (*label, *color))
+        .chain([("edge", EDGE)])
+        .collect();
+
+    Ok(Image {
+        image: with_legend(canvas, &entries),
+    })
+}
+
+// Number of nodes of each kind, in the order of `KINDS`.
+pub fn count_kinds(maze: &Maze) -> Vec<(&'static str, usize)> {
+    let mut counts: FxHashMap<NodeKind, usize> = FxHashMap::default();
+    for node in maze.data.values() {
+        *counts.entry(node.kind()).or_default() += 1;
+    }
+    KINDS
+        .iter()
+        .map(|(kind, label, _)| (*label, counts.get(kind).copied().unwrap_or(0)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+
+    #[test]
+    fn node_kinds() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0, 0],
+            [0, 1, 0, 1, 1, 0],
+            [0, 1, 1, 1, 0, 0],
+            [0, 1, 0, 0, 0, 0],
+            [0, 1, 0, 0, 0, 0]
+        ]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+
+        assert_eq!(
+            vec![
+                ("start", 1),
+                ("exit", 1),
+                ("junction", 1),
+                ("corner", 2),
+                ("corridor", 0),
+                ("dead end", 1)
+            ],
+            count_kinds(&maze)
+        );
+
+        let rendered = render(&img, &maze, &RenderOptions::default()).unwrap();
+        let image = &rendered.image;
+        assert_eq!(&kind_color(NodeKind::Start), image.get_pixel(1, 0));
+        assert_eq!(&kind_color(NodeKind::Junction), image.get_pixel(1, 2));
+        assert_eq!(&EDGE, image.get_pixel(2, 2));
+        assert_eq!(&kind_color(NodeKind::Corner), image.get_pixel(3, 2));
+        assert_eq!(&kind_color(NodeKind::Corner), image.get_pixel(3, 1));
+        assert_eq!(&kind_color(NodeKind::DeadEnd), image.get_pixel(4, 1));
+        assert_eq!(&kind_color(NodeKind::Exit), image.get_pixel(1, 4));
+    }
+}
@@ -0,0 +1,162 @@
+use image::{Rgb, RgbImage};
+
+use crate::{
+    img::Image,
+    maze::{PATHWAY, WALL},
+};
+
+// Small SplitMix64 generator, good enough to shuffle mazes and reproducible from a seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in `0..bound`.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % bound as u64) as u32
+    }
+
+    // True with the given probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        ((self.next_u64() >> 40) as f32 / (1u64 << 24) as f32) < probability
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    // Size of the maze in cells, the image becomes `2 * width + 1` pixels wide.
+    pub width: u32,
+    pub height: u32,
+    pub seed: u64,
+    // Share of dead ends that get opened up into loops, 0.0 gives a perfect maze.
+    pub braid: f32,
+}
+
+const NEIGHBOURS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Carves a maze with a randomized depth first search, with the entrance in the top row and the exit
+// in the bottom row.
+pub fn generate(options: &GeneratorOptions) -> Image {
+    let (width, height) = (options.width.max(1), options.height.max(1));
+    let mut rng = Rng::new(options.seed);
+    let mut image = RgbImage::from_pixel(2 * width + 1, 2 * height + 1, Rgb(WALL));
+
+    let cell = |x: u32, y: u32| (2 * x + 1, 2 * y + 1);
+    let mut visited = vec![false; (width * height) as usize];
+
+    let start = (rng.below(width), rng.below(height));
+    visited[(start.1 * width + start.0) as usize] = true;
+    let (px, py) = cell(start.0, start.1);
+    image.put_pixel(px, py, Rgb(PATHWAY));
+
+    let mut stack = vec![start];
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited: Vec<(u32, u32)> = NEIGHBOURS
+            .iter()
+            .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(|(nx, ny)| *nx >= 0 && *ny >= 0 && *nx < width as i64 && *ny < height as i64)
+            .map(|(nx, ny)| (nx as u32, ny as u32))
+            .filter(|(nx, ny)| !visited[(ny * width + nx) as usize])
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (nx, ny) = unvisited[rng.below(unvisited.len() as u32) as usize];
+        visited[(ny * width + nx) as usize] = true;
+        let (cx, cy) = cell(nx, ny);
+        image.put_pixel(cx, cy, Rgb(PATHWAY));
+        image.put_pixel(x + nx + 1, y + ny + 1, Rgb(PATHWAY));
+        stack.push((nx, ny));
+    }
+
+    if options.braid > 0.0 {
+        braid(&mut image, width, height, options.braid, &mut rng);
+    }
+
+    let entrance = cell(rng.below(width), 0).0;
+    let exit = cell(rng.below(width), 0).0;
+    image.put_pixel(entrance, 0, Rgb(PATHWAY));
+    image.put_pixel(exit, 2 * height, Rgb(PATHWAY));
+
+    Image { image }
+}
+
+// Knocks a wall out of dead end cells to turn the perfect maze into one with loops.
+fn braid(image: &mut RgbImage, width: u32, height: u32, share: f32, rng: &mut Rng) {
+    for y in 0..height {
+        for x in 0..width {
+            let (cx, cy) = (2 * x + 1, 2 * y + 1);
+            let walls: Vec<(u32, u32)> = NEIGHBOURS
+                .iter()
+                .map(|(dx, dy)| ((cx as i64 + dx) as u32, (cy as i64 + dy) as u32))
+                .filter(|(wx, wy)| image.get_pixel(*wx, *wy).0 == WALL)
+                .collect();
+            if walls.len() != 3 || !rng.chance(share) {
+                continue;
+            }
+
+            let inner: Vec<&(u32, u32)> = walls
+                .iter()
+                .filter(|(wx, wy)| *wx > 0 && *wy > 0 && *wx < 2 * width && *wy < 2 * height)
+                .collect();
+            if inner.is_empty() {
+                continue;
+            }
+            let (wx, wy) = inner[rng.below(inner.len() as u32) as usize];
+            image.put_pixel(*wx, *wy, Rgb(PATHWAY));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        algorithms::{breadth_first::BreadthFirst, Solver},
+        maze::Maze,
+        statistics::Statistics,
+    };
+
+    #[test]
+    fn generated_maze_is_solvable() {
+        for seed in 0..20 {
+            let options = GeneratorOptions {
+                width: 12,
+                height: 9,
+                seed,
+                braid: if seed % 2 == 0 { 0.0 } else { 0.5 },
+            };
+            let image = generate(&options);
+            assert_eq!((25, 19), image.image.dimensions());
+
+            let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+            let solution = BreadthFirst.solve(&maze).unwrap();
+            assert!(solution.path.front().unwrap().end);
+            assert!(solution.path.back().unwrap().start);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        let options = GeneratorOptions {
+            width: 10,
+            height: 10,
+            seed: 42,
+            braid: 0.3,
+        };
+        assert_eq!(generate(&options).image, generate(&options).image);
+    }
+}
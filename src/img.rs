@@ -1,32 +1,311 @@
-use std::path::Path;
+use std::{
+    io::{stdin, stdout, Cursor, Read, Write},
+    path::Path,
+};
 
-use image::{open, ImageBuffer, Rgb};
+use image::{
+    imageops, io::Reader, load_from_memory, ImageBuffer, ImageError, ImageFormat, Rgb, RgbImage,
+};
 
-use crate::{algorithms::Solution, node::Point};
+use crate::{
+    algorithms::Solution,
+    colors::PathColor,
+    maze::{PATHWAY, WALL},
+    node::Point,
+};
 
 pub struct Image {
     pub image: ImageBuffer<Rgb<u8>, Vec<u8>>,
 }
 
+// A rectangle in maze pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Rendered images larger than this many pixels are refused rather than allocated.
+pub const MAX_OUTPUT_PIXELS: u64 = 1 << 28;
+
+impl Region {
+    pub(crate) fn contains(&self, point: &Point) -> bool {
+        point.x >= self.x
+            && point.y >= self.y
+            && point.x - self.x < self.width
+            && point.y - self.y < self.height
+    }
+
+    // Checks the region lies within a `width` x `height` maze and scaled up stays a sane size.
+    pub(crate) fn check(&self, width: u32, height: u32, scale: u32) -> Result<(), String> {
+        let inside = |start: u32, size: u32, limit: u32| {
+            start.checked_add(size).is_some_and(|end| end <= limit)
+        };
+        if !inside(self.x, self.width, width) || !inside(self.y, self.height, height) {
+            return Err(format!(
+                "crop region {},{},{},{} is outside of the {width}x{height} maze",
+                self.x, self.y, self.width, self.height
+            ));
+        }
+        let (output_width, output_height) = (
+            self.width as u64 * scale as u64,
+            self.height as u64 * scale as u64,
+        );
+        if output_width > u32::MAX as u64
+            || output_height > u32::MAX as u64
+            || output_width * output_height > MAX_OUTPUT_PIXELS
+        {
+            return Err(format!(
+                "the output would be {output_width}x{output_height} pixels, lower the scale or crop it"
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Parses a region written as `x,y,width,height`.
+pub fn parse_region(value: &str) -> Result<Region, String> {
+    let parts: Vec<u32> = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid region '{value}', expected x,y,width,height"))?;
+    match parts[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Region {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => Err(format!(
+            "invalid region '{value}', expected x,y,width,height with a non zero size"
+        )),
+    }
+}
+
+// Parses a point written as `x,y`.
+pub fn parse_point(value: &str) -> Result<Point, String> {
+    let parts: Vec<u32> = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid point '{value}', expected x,y"))?;
+    match parts[..] {
+        [x, y] => Ok(Point::at(x, y)),
+        _ => Err(format!("invalid point '{value}', expected x,y")),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    // Every maze pixel becomes a `scale` x `scale` block.
+    pub scale: u32,
+    // Width of the solution line in output pixels, defaults to the full block.
+    pub line_width: Option<u32>,
+    pub crop: Option<Region>,
+    // Colors the walls and pathways are drawn with.
+    pub wall: Rgb<u8>,
+    pub path: Rgb<u8>,
+}
+
+impl RenderOptions {
+    // True when rendering would give back the maze image as it is.
+    pub fn is_identity(&self) -> bool {
+        self.scale == 1 && self.crop.is_none() && self.wall.0 == WALL && self.path.0 == PATHWAY
+    }
+
+    // The region that ends up in the output, the whole maze unless cropped.
+    pub fn region(&self, width: u32, height: u32) -> Region {
+        self.crop.unwrap_or(Region {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        })
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            line_width: None,
+            crop: None,
+            wall: Rgb(WALL),
+            path: Rgb(PATHWAY),
+        }
+    }
+}
+
+// Used instead of a filename to read from stdin or write to stdout.
+pub const STDIO: &str = "-";
+
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO)
+}
+
 impl Image {
-    pub fn open(path: &Path) -> Self {
-        let image = open(path).unwrap().into_rgb8();
-        Self { image }
+    // Opens a maze from a file, or from stdin for `-`. The format is detected from the content, so
+    // the file extension doesn't matter.
+    pub fn open(path: &Path) -> Result<Self, ImageError> {
+        let image = if is_stdio(path) {
+            let mut bytes = Vec::new();
+            stdin().read_to_end(&mut bytes)?;
+            load_from_memory(&bytes)?
+        } else {
+            Reader::open(path)?.with_guessed_format()?.decode()?
+        };
+        Ok(Self {
+            image: image.into_rgb8(),
+        })
+    }
+
+    pub fn apply_solution(&mut self, solution: &Solution, color: &PathColor) {
+        let pixels = solution.pixels();
+        let region = Region {
+            x: 0,
+            y: 0,
+            width: self.image.width(),
+            height: self.image.height(),
+        };
+        paint_path(&mut self.image, &pixels, color, &region, 1, 1);
+    }
+
+    // Renders a new image cropped to the region of interest and upscaled with nearest neighbour,
+    // with the solution drawn on top.
+    pub fn render(
+        &self,
+        solution: Option<&Solution>,
+        color: &PathColor,
+        options: &RenderOptions,
+    ) -> Result<Image, String> {
+        let (width, height) = self.image.dimensions();
+        let region = options.region(width, height);
+        let scale = options.scale.max(1);
+        region.check(width, height, scale)?;
+
+        let mut cropped =
+            imageops::crop_imm(&self.image, region.x, region.y, region.width, region.height)
+                .to_image();
+        for pixel in cropped.pixels_mut() {
+            if pixel.0 == WALL {
+                *pixel = options.wall;
+            } else if pixel.0 == PATHWAY {
+                *pixel = options.path;
+            }
+        }
+        let mut image = imageops::resize(
+            &cropped,
+            region.width * scale,
+            region.height * scale,
+            imageops::FilterType::Nearest,
+        );
+
+        if let Some(solution) = solution {
+            let line_width = options.line_width.unwrap_or(scale).clamp(1, scale);
+            paint_path(
+                &mut image,
+                &solution.pixels(),
+                color,
+                &region,
+                scale,
+                line_width,
+            );
+        }
+        Ok(Image { image })
     }
 
-    pub fn apply_solution(&mut self, solution: &mut Solution) {
-        let mut last = solution.path.pop_front().unwrap();
-        for n in &solution.path {
-            let line = line(&last.point, &n.point);
-            for point in line {
-                self.image.put_pixel(point.x, point.y, Rgb([255, 0, 0]));
+    // Saves the image to a file, or to stdout for `-`.
+    pub fn save(&self, output: &Path, format: ImageFormat) -> Result<(), ImageError> {
+        if !is_stdio(output) {
+            return self.image.save_with_format(output, format);
+        }
+        // Encoders need to seek, so the image is encoded in memory first
+        let mut bytes = Cursor::new(Vec::new());
+        self.image.write_to(&mut bytes, format)?;
+        stdout().lock().write_all(bytes.get_ref())?;
+        Ok(())
+    }
+}
+
+// Draws the path as a line of `line_width` through the center of each `scale` sized block, skipping
+// everything outside of `region`.
+fn paint_path(
+    image: &mut RgbImage,
+    pixels: &[Point],
+    color: &PathColor,
+    region: &Region,
+    scale: u32,
+    line_width: u32,
+) {
+    let last = pixels.len().saturating_sub(1).max(1) as f32;
+    paint_segments(
+        image,
+        pixels,
+        |i| Some(color.at(i as f32 / last)),
+        region,
+        scale,
+        line_width,
+    );
+}
+
+// Same as `paint_path` but the color of every pixel, and the step leading out of it, is picked by
+// `color_at` from its index in the path. Pixels without a color are left untouched.
+pub(crate) fn paint_segments(
+    image: &mut RgbImage,
+    pixels: &[Point],
+    color_at: impl Fn(usize) -> Option<Rgb<u8>>,
+    region: &Region,
+    scale: u32,
+    line_width: u32,
+) {
+    let offset = (scale - line_width) / 2;
+
+    let mut fill = |from: &Point, to: &Point, color: Rgb<u8>| {
+        if !region.contains(from) || !region.contains(to) {
+            return;
+        }
+        let x0 = (from.x.min(to.x) - region.x) * scale + offset;
+        let y0 = (from.y.min(to.y) - region.y) * scale + offset;
+        let x1 = (from.x.max(to.x) - region.x) * scale + offset + line_width;
+        let y1 = (from.y.max(to.y) - region.y) * scale + offset + line_width;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                image.put_pixel(x, y, color);
             }
-            last = n;
+        }
+    };
+
+    for (i, point) in pixels.iter().enumerate() {
+        let Some(color) = color_at(i) else {
+            continue;
+        };
+        fill(point, point, color);
+        if let Some(next) = pixels.get(i + 1) {
+            fill(point, next, color);
         }
     }
+}
 
-    pub fn save(&self, output: &std::path::Path) -> Result<(), image::ImageError> {
-        self.image.save(output)
+// Fills the whole `scale` sized block of a maze pixel.
+pub(crate) fn fill_block(
+    image: &mut RgbImage,
+    point: &Point,
+    region: &Region,
+    scale: u32,
+    color: Rgb<u8>,
+) {
+    if !region.contains(point) {
+        return;
+    }
+    let x0 = (point.x - region.x) * scale;
+    let y0 = (point.y - region.y) * scale;
+    for y in y0..y0 + scale {
+        for x in x0..x0 + scale {
+            image.put_pixel(x, y, color);
+        }
     }
 }
 
@@ -73,6 +352,137 @@ pub(crate) fn line(p1: &Point, p2: &Point) -> Vec<Point> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::test_utils::{maze_image, PATH, WALL};
+    use crate::{
+        algorithms::{breadth_first::BreadthFirst, Solver},
+        maze::Maze,
+        statistics::Statistics,
+    };
+
+    #[test]
+    pub fn gradient_solution() {
+        let mut img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 1, 0],
+            [0, 0, 0, 1, 0]
+        ]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = BreadthFirst.solve(&maze).unwrap();
+
+        let color = PathColor::Gradient(Rgb([0, 0, 255]), Rgb([0, 255, 0]));
+        img.apply_solution(&solution, &color);
+
+        assert_eq!(&Rgb([0, 0, 255]), img.image.get_pixel(1, 0));
+        assert_eq!(&Rgb([0, 128, 128]), img.image.get_pixel(3, 1));
+        assert_eq!(&Rgb([0, 255, 0]), img.image.get_pixel(3, 4));
+    }
+
+    #[test]
+    pub fn scaled_thin_solution() {
+        let img = maze_image!([[0, 1, 0], [0, 1, 0], [0, 1, 0]]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = BreadthFirst.solve(&maze).unwrap();
+
+        let options = RenderOptions {
+            scale: 3,
+            line_width: Some(1),
+            crop: None,
+            ..RenderOptions::default()
+        };
+        let rendered = img
+            .render(Some(&solution), &PathColor::default(), &options)
+            .unwrap();
+
+        assert_eq!((9, 9), rendered.image.dimensions());
+        assert_eq!(&WALL, rendered.image.get_pixel(1, 4));
+        assert_eq!(&PATH, rendered.image.get_pixel(3, 4));
+        assert_eq!(&PATH, rendered.image.get_pixel(4, 0));
+        assert_eq!(&Rgb([255, 0, 0]), rendered.image.get_pixel(4, 1));
+        assert_eq!(&Rgb([255, 0, 0]), rendered.image.get_pixel(4, 4));
+        assert_eq!(&Rgb([255, 0, 0]), rendered.image.get_pixel(4, 7));
+        assert_eq!(&PATH, rendered.image.get_pixel(5, 4));
+    }
+
+    #[test]
+    pub fn cropped_render() {
+        let img = maze_image!([[0, 1, 0, 0, 0], [0, 1, 1, 1, 0], [0, 0, 0, 1, 0]]);
+        let options = RenderOptions {
+            scale: 2,
+            line_width: None,
+            crop: Some(Region {
+                x: 2,
+                y: 1,
+                width: 3,
+                height: 2,
+            }),
+            wall: Rgb([0, 0, 128]),
+            ..RenderOptions::default()
+        };
+        let rendered = img.render(None, &PathColor::default(), &options).unwrap();
+
+        assert_eq!((6, 4), rendered.image.dimensions());
+        assert_eq!(&PATH, rendered.image.get_pixel(0, 0));
+        assert_eq!(&Rgb([0, 0, 128]), rendered.image.get_pixel(5, 3));
+
+        let options = RenderOptions {
+            crop: Some(Region {
+                x: 3,
+                y: 1,
+                width: 3,
+                height: 2,
+            }),
+            ..options
+        };
+        assert!(img.render(None, &PathColor::default(), &options).is_err());
+    }
+
+    #[test]
+    pub fn region() {
+        assert_eq!(
+            Ok(Region {
+                x: 1,
+                y: 2,
+                width: 30,
+                height: 40
+            }),
+            parse_region("1,2,30,40")
+        );
+        assert!(parse_region("1,2,30").is_err());
+        assert!(parse_region("1,2,0,40").is_err());
+        assert_eq!(Ok(Point::at(3, 7)), parse_point("3, 7"));
+        assert!(parse_point("3").is_err());
+    }
+
+    #[test]
+    pub fn region_bounds() {
+        let region = |x, y, width, height| Region {
+            x,
+            y,
+            width,
+            height,
+        };
+        assert!(region(0, 0, 10, 10).check(10, 10, 4).is_ok());
+        assert!(region(u32::MAX, 0, 2, 2).check(10, 10, 1).is_err());
+        assert!(region(8, 8, 4, 1).check(10, 10, 1).is_err());
+        assert!(region(0, 0, 10, 10).check(10, 10, u32::MAX).is_err());
+        assert!(region(0, 0, 10, 10).check(10, 10, 5_000).is_err());
+
+        assert!(!region(u32::MAX, 0, 2, 2).contains(&Point::at(1, 1)));
+        assert!(region(u32::MAX - 1, 0, 1, 1).contains(&Point::at(u32::MAX - 1, 0)));
+    }
+
+    #[test]
+    pub fn format_from_content() {
+        let img = maze_image!([[0, 1, 0], [0, 1, 0], [0, 1, 0]]);
+        // A bmp with a png extension still opens
+        let path = std::env::temp_dir().join("theseus_format_from_content.png");
+        img.save(&path, ImageFormat::Bmp).unwrap();
+        assert_eq!(b"BM", &std::fs::read(&path).unwrap()[..2]);
+        assert_eq!(img.image, Image::open(&path).unwrap().image);
+        std::fs::remove_file(path).unwrap();
+    }
 
     #[test]
     pub fn east_line() {
@@ -0,0 +1,100 @@
+use std::{
+    io::{stdout, IsTerminal},
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+use spinners::{Spinner, Spinners};
+
+use crate::{cli::GlobalArgs, progress::Bar};
+
+// How much the program tells about what it is doing. Results, like a saved solution or a json
+// report, and errors are always printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet,
+    Info,
+    Verbose,
+    Debug,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+static BANNERS: AtomicBool = AtomicBool::new(true);
+
+pub fn init(global: &GlobalArgs) {
+    let level = match (global.quiet, global.verbose) {
+        (true, _) => Level::Quiet,
+        (false, 0) => Level::Info,
+        (false, 1) => Level::Verbose,
+        (false, _) => Level::Debug,
+    };
+    set_level(level);
+    BANNERS.store(!global.no_banner, Ordering::Relaxed);
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+pub fn banner(banner: &str) {
+    if enabled(Level::Info) && BANNERS.load(Ordering::Relaxed) {
+        println!("{banner}");
+    }
+}
+
+// Spinners only make sense in a terminal, anywhere else the message is printed as a plain line so
+// logs don't fill up with animation frames.
+pub fn spinner(message: String) -> Option<Spinner> {
+    if !enabled(Level::Info) {
+        return None;
+    }
+    if !stdout().is_terminal() {
+        println!("{message}");
+        return None;
+    }
+    Some(Spinner::new(Spinners::Dots12, message))
+}
+
+// Same as a spinner, but for work that knows how far along it is.
+pub fn progress(message: String) -> Bar {
+    let visible = enabled(Level::Info) && stdout().is_terminal();
+    if enabled(Level::Info) && !visible {
+        println!("{message}");
+    }
+    Bar::new(message, visible)
+}
+
+pub fn stop(spinner: Option<Spinner>) {
+    if let Some(mut spinner) = spinner {
+        spinner.stop_with_newline();
+    }
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            println!($($arg)*);
+        }
+    };
+}
+
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Verbose) {
+            println!($($arg)*);
+        }
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {debug, info, verbose};
@@ -1,21 +1,36 @@
 use fxhash::FxHashMap;
 use image::{Pixel, RgbImage};
-use std::{io::Error, iter};
+use std::{
+    io::{Error, ErrorKind},
+    iter,
+};
 
 use crate::img::Image;
 use crate::node::{Direction, Node, NodeType, Point};
+use crate::progress::{NoProgress, Progress, Stage};
 use crate::statistics::Statistics;
-use crate::utils::{look_ahead, path_above, path_below, wall_above, wall_below};
+use crate::utils::{get_dist, look_ahead, path_above, path_below, wall_above, wall_below};
 
 #[derive(Debug)]
 pub struct Maze {
     pub width: u32,
     pub height: u32,
     pub data: Nodes,
+    // Edges that turn corners, left by simplifying the maze. Every other edge is a straight line.
+    pub edges: Edges,
 }
 
 pub type Nodes = FxHashMap<NodeType, Node>;
 
+// An edge from one node to the next, with the corners it passes on the way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub length: u32,
+    pub bends: Vec<Point>,
+}
+
+pub type Edges = FxHashMap<(Point, Point), Edge>;
+
 pub const WALL: [u8; 3] = [0, 0, 0];
 pub const PATHWAY: [u8; 3] = [255, 255, 255];
 
@@ -33,11 +48,20 @@ fn get_entrance<'a>(image: &RgbImage, nodes: &'a mut Nodes, top_nodes: &'a mut [
     }
 }
 
-fn get_exit<'a>(image: &RgbImage, nodes: &'a mut Nodes, top_nodes: &'a [Option<Point>]) {
+fn get_exit<'a>(
+    image: &RgbImage,
+    nodes: &'a mut Nodes,
+    top_nodes: &'a [Option<Point>],
+) -> Result<(), Error> {
     let last_row = image.height() - 1;
-    for (x, pixel) in image.rows().last().unwrap().enumerate() {
+    for (x, pixel) in image.rows().next_back().unwrap().enumerate() {
         if pixel.channels() == PATHWAY {
-            let above = top_nodes[x].as_ref().unwrap();
+            let Some(above) = top_nodes[x].as_ref() else {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("the exit at {x},{last_row} has no path above it"),
+                ));
+            };
             let current = Point::at(x as u32, last_row);
 
             let mut exit_node = Node::exit(current.x, current.y);
@@ -56,10 +80,20 @@ fn get_exit<'a>(image: &RgbImage, nodes: &'a mut Nodes, top_nodes: &'a [Option<P
             break;
         }
     }
+    Ok(())
 }
 
 impl Maze {
     pub(crate) fn from_image(image: &Image, statistics: &mut Statistics) -> Result<Maze, Error> {
+        Maze::from_image_with_progress(image, statistics, &NoProgress)
+    }
+
+    // Analyzes the image, reporting every row it has gone through.
+    pub(crate) fn from_image_with_progress(
+        image: &Image,
+        statistics: &mut Statistics,
+        progress: &dyn Progress,
+    ) -> Result<Maze, Error> {
         let image = &image.image;
         let len = image.pixels().len();
         let mut nodes = FxHashMap::with_capacity_and_hasher(len / 6, Default::default());
@@ -70,16 +104,20 @@ impl Maze {
         get_entrance(image, &mut nodes, &mut top_nodes);
 
         let (width, height) = image.dimensions();
+        progress.start(Stage::Analyze, height as u64);
         if height == 1 && nodes.len() == 1 {
+            progress.finish(Stage::Analyze);
             return Ok(Maze {
                 width,
                 height,
                 data: nodes,
+                edges: Edges::default(),
             });
         }
 
         if height > 2 {
             for y in 1..height - 1 {
+                progress.advance(Stage::Analyze, y as u64);
                 let mut current = false;
                 let mut next = look_ahead(0, y, image);
 
@@ -189,8 +227,9 @@ impl Maze {
         }
 
         if height > 1 {
-            get_exit(image, &mut nodes, &top_nodes);
+            get_exit(image, &mut nodes, &top_nodes)?;
         }
+        progress.finish(Stage::Analyze);
 
         statistics.img.height = image.height();
         statistics.img.width = image.width();
@@ -201,42 +240,59 @@ impl Maze {
             width: image.width(),
             height: image.height(),
             data: nodes,
+            edges: Edges::default(),
         })
     }
+
+    // The node at a point, whichever kind it is.
+    pub(crate) fn node_at(&self, point: Point) -> Option<&Node> {
+        let data = &self.data;
+        data.get(&NodeType::Path(point))
+            .or_else(|| {
+                data.get(&NodeType::Start)
+                    .filter(|node| node.point == point)
+            })
+            .or_else(|| data.get(&NodeType::Exit).filter(|node| node.point == point))
+    }
+
+    // Number of pixels walked along the edge between two neighbouring nodes.
+    pub(crate) fn distance(&self, from: Point, to: Point) -> u32 {
+        if self.edges.is_empty() {
+            return get_dist(&from, &to);
+        }
+        self.edges
+            .get(&(from, to))
+            .map_or_else(|| get_dist(&from, &to), |edge| edge.length)
+    }
+
+    // The corners on the edge between two neighbouring nodes, in the order they are walked.
+    pub(crate) fn bends(&self, from: Point, to: Point) -> &[Point] {
+        self.edges
+            .get(&(from, to))
+            .map_or(&[], |edge| edge.bends.as_slice())
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.data
+            .values()
+            .map(|node| node.children.len())
+            .sum::<usize>()
+            / 2
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
-    use image::Rgb;
-
-    const WALL: Rgb<u8> = Rgb([0, 0, 0]);
-    const PATH: Rgb<u8> = Rgb([255, 255, 255]);
-
-    macro_rules! maze_image {
-        ($num:expr) => {{
-            let mut image = RgbImage::new($num[0].len() as u32, $num.len() as u32);
-            for (y, row) in $num.iter().enumerate() {
-                for (x, item) in row.iter().enumerate() {
-                    // if path
-                    if *item == 1 {
-                        image.put_pixel(x as u32, y as u32, PATH);
-                    // if wall
-                    } else {
-                        image.put_pixel(x as u32, y as u32, WALL);
-                    }
-                }
-            }
-            Image { image }
-        }};
-    }
+    use crate::test_utils::maze_image;
+
     //     // first maze row
     //     // xox
     #[test]
     fn entrance() {
         let img = maze_image!([[0, 1, 0]]);
-        let maze_tree = Maze::from_image(&img).unwrap();
+        let maze_tree = Maze::from_image(&img, &mut Statistics::default()).unwrap();
 
         let node = &maze_tree.data[&NodeType::Start];
         assert_eq!(&Node::start(1, 0), node)
@@ -253,7 +309,7 @@ mod test {
             [0, 1, 0]
         ]);
 
-        let maze_tree = Maze::from_image(&img).unwrap();
+        let maze_tree = Maze::from_image(&img, &mut Statistics::default()).unwrap();
 
         let mut start = Node::start(1, 0);
         start.children.insert(Direction::South, Point::at(1, 1));
@@ -278,7 +334,7 @@ mod test {
             [0, 1, 0]
         ]);
 
-        let maze_tree = Maze::from_image(&img).unwrap();
+        let maze_tree = Maze::from_image(&img, &mut Statistics::default()).unwrap();
 
         let mut n1 = Node::start(1, 0);
         n1.children.insert(Direction::South, Point::at(1, 2));
@@ -299,7 +355,7 @@ mod test {
             [0, 1, 0, 0]
         ]);
 
-        let maze_tree = Maze::from_image(&img).unwrap();
+        let maze_tree = Maze::from_image(&img, &mut Statistics::default()).unwrap();
 
         let mut n1 = Node::start(1, 0);
         n1.children.insert(Direction::South, Point::at(1, 1));
@@ -344,7 +400,7 @@ mod test {
             [0, 1, 0, 0, 0]
         ]);
 
-        let maze_tree = Maze::from_image(&img).unwrap();
+        let maze_tree = Maze::from_image(&img, &mut Statistics::default()).unwrap();
 
         let mut n1 = Node::start(1, 0);
         let mut n2 = Node::at(3, 1);
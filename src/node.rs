@@ -1,5 +1,7 @@
 use std::{collections::HashMap, ops};
 
+use serde::Serialize;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Node {
     pub start: bool,
@@ -35,9 +37,41 @@ impl Node {
             children: HashMap::new(),
         }
     }
+
+    pub fn kind(&self) -> NodeKind {
+        if self.start {
+            return NodeKind::Start;
+        }
+        if self.end {
+            return NodeKind::Exit;
+        }
+        match self.children.len() {
+            0 | 1 => NodeKind::DeadEnd,
+            2 => {
+                let straight = self.children.contains_key(&Direction::North)
+                    == self.children.contains_key(&Direction::South);
+                if straight {
+                    NodeKind::Corridor
+                } else {
+                    NodeKind::Corner
+                }
+            }
+            _ => NodeKind::Junction,
+        }
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum NodeKind {
+    Start,
+    Exit,
+    Junction,
+    Corner,
+    Corridor,
+    DeadEnd,
 }
 
-#[derive(Debug, Default, Hash, Eq, PartialEq, Copy, Clone, PartialOrd, Ord)]
+#[derive(Debug, Default, Hash, Eq, PartialEq, Copy, Clone, PartialOrd, Ord, Serialize)]
 pub struct Point {
     pub x: u32,
     pub y: u32,
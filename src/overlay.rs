@@ -0,0 +1,192 @@
+use fxhash::{FxHashMap, FxHashSet};
+use image::{Rgb, RgbImage};
+
+use crate::{
+    colors::{Colormap, PathColor},
+    font::{draw_text, text_width, GLYPH_HEIGHT},
+    img::{fill_block, paint_segments, Image, RenderOptions},
+    node::Point,
+};
+
+// Distinct colors handed out to the paths in the order they are compared.
+pub const PALETTE: [Rgb<u8>; 8] = [
+    Rgb([230, 25, 75]),
+    Rgb([0, 130, 200]),
+    Rgb([60, 180, 75]),
+    Rgb([245, 130, 48]),
+    Rgb([145, 30, 180]),
+    Rgb([70, 240, 240]),
+    Rgb([240, 50, 230]),
+    Rgb([128, 0, 0]),
+];
+
+// Colors for `count` paths, the palette while it lasts and spread over a colormap beyond that.
+pub fn colors(count: usize) -> Vec<Rgb<u8>> {
+    if count <= PALETTE.len() {
+        return PALETTE[..count].to_vec();
+    }
+    (0..count)
+        .map(|i| Colormap::Rainbow.sample(i as f32 / (count - 1) as f32))
+        .collect()
+}
+
+pub const SHARED: Rgb<u8> = Rgb([160, 160, 160]);
+pub const DIVERGENCE: Rgb<u8> = Rgb([255, 215, 0]);
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const TEXT: Rgb<u8> = Rgb([0, 0, 0]);
+
+pub struct Layer {
+    pub label: String,
+    pub pixels: Vec<Point>,
+    pub color: Rgb<u8>,
+}
+
+// Renders several paths on top of the same maze. Stretches every path has in common are drawn in
+// a neutral color, the points where they split up or join again are highlighted and a legend strip
+// is added below the maze.
+pub fn render(image: &Image, layers: &[Layer], options: &RenderOptions) -> Result<Image, String> {
+    let mut canvas = image.render(None, &PathColor::default(), options)?.image;
+    let (width, height) = image.image.dimensions();
+    let region = options.region(width, height);
+    let scale = options.scale.max(1);
+    let line_width = options.line_width.unwrap_or(scale).clamp(1, scale);
+
+    let mut usage: FxHashMap<Point, usize> = FxHashMap::default();
+    for layer in layers {
+        let unique: FxHashSet<&Point> = layer.pixels.iter().collect();
+        for point in unique {
+            *usage.entry(*point).or_default() += 1;
+        }
+    }
+    let shared = |point: &Point| layers.len() > 1 && usage[point] == layers.len();
+
+    let mut divergences = FxHashSet::default();
+    for layer in layers {
+        let pixels = &layer.pixels;
+        for pair in pixels.windows(2) {
+            match (shared(&pair[0]), shared(&pair[1])) {
+                (true, false) => divergences.insert(pair[0]),
+                (false, true) => divergences.insert(pair[1]),
+                _ => false,
+            };
+        }
+
+        let color_at = |i: usize| {
+            let leaves_shared = pixels.get(i + 1).is_none_or(&shared);
+            if shared(&pixels[i]) && leaves_shared {
+                Some(SHARED)
+            } else {
+                Some(layer.color)
+            }
+        };
+        paint_segments(&mut canvas, pixels, color_at, &region, scale, line_width);
+    }
+
+    for point in &divergences {
+        fill_block(&mut canvas, point, &region, scale, DIVERGENCE);
+    }
+
+    let mut entries: Vec<(&str, Rgb<u8>)> = layers
+        .iter()
+        .map(|layer| (layer.label.as_str(), layer.color))
+        .collect();
+    if layers.len() > 1 {
+        entries.push(("shared", SHARED));
+        entries.push(("diverges", DIVERGENCE));
+    }
+
+    Ok(Image {
+        image: with_legend(canvas, &entries),
+    })
+}
+
+// Appends a strip below the image with one colored swatch and label per row.
+pub(crate) fn with_legend(image: RgbImage, entries: &[(&str, Rgb<u8>)]) -> RgbImage {
+    let text_scale = (image.width() / 150).clamp(1, 4);
+    let swatch = GLYPH_HEIGHT * text_scale;
+    let padding = 2 * text_scale;
+    let row_height = swatch + padding;
+
+    let legend_width = entries
+        .iter()
+        .map(|(label, _)| 3 * padding + swatch + text_width(label, text_scale))
+        .max()
+        .unwrap_or(0);
+    let legend_height = entries.len() as u32 * row_height + padding;
+
+    let width = image.width().max(legend_width);
+    let mut canvas = RgbImage::from_pixel(width, image.height() + legend_height, BACKGROUND);
+    image::imageops::replace(&mut canvas, &image, 0, 0);
+
+    for (i, (label, color)) in entries.iter().enumerate() {
+        let top = image.height() + padding + i as u32 * row_height;
+        for y in top..top + swatch {
+            for x in padding..padding + swatch {
+                canvas.put_pixel(x, y, *color);
+            }
+        }
+        draw_text(
+            &mut canvas,
+            2 * padding + swatch,
+            top,
+            label,
+            text_scale,
+            TEXT,
+        );
+    }
+    canvas
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::maze_image;
+
+    fn points(coords: &[(u32, u32)]) -> Vec<Point> {
+        coords.iter().map(|(x, y)| Point::at(*x, *y)).collect()
+    }
+
+    #[test]
+    fn shared_and_diverging_paths() {
+        let img = maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0],
+            [0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0]
+        ]);
+        let left = Layer {
+            label: "left".into(),
+            pixels: points(&[(1, 0), (1, 1), (1, 2), (1, 3), (1, 4)]),
+            color: PALETTE[0],
+        };
+        let right = Layer {
+            label: "right".into(),
+            pixels: points(&[
+                (1, 0),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (3, 3),
+                (2, 3),
+                (1, 3),
+                (1, 4),
+            ]),
+            color: PALETTE[1],
+        };
+
+        let rendered = render(&img, &[left, right], &RenderOptions::default()).unwrap();
+        let image = &rendered.image;
+
+        assert_eq!(&SHARED, image.get_pixel(1, 0));
+        assert_eq!(&DIVERGENCE, image.get_pixel(1, 1));
+        assert_eq!(&PALETTE[0], image.get_pixel(1, 2));
+        assert_eq!(&PALETTE[1], image.get_pixel(3, 2));
+        assert_eq!(&DIVERGENCE, image.get_pixel(1, 3));
+        assert_eq!(&SHARED, image.get_pixel(1, 4));
+        assert!(image.height() > 5);
+        assert_eq!(&PALETTE[0], image.get_pixel(2, 7));
+    }
+}
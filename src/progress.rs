@@ -0,0 +1,153 @@
+use std::{
+    io::{stdout, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// The work being reported on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Analyze,
+    Solve,
+}
+
+// Receives how far along analyzing or solving a maze is. Analysis counts the rows of the image,
+// solving the nodes a solver expanded out of all nodes in the maze, which a solver can pass when it
+// visits nodes more than once.
+pub trait Progress: Send + Sync {
+    fn start(&self, _stage: Stage, _total: u64) {}
+
+    fn advance(&self, stage: Stage, done: u64);
+
+    fn finish(&self, _stage: Stage) {}
+}
+
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn advance(&self, _stage: Stage, _done: u64) {}
+}
+
+const BAR_WIDTH: usize = 30;
+// Redrawing on every update would slow down the work it reports on
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+// A progress bar with an estimate of the time left, redrawn in place on the terminal.
+pub struct Bar {
+    message: String,
+    visible: bool,
+    state: Mutex<BarState>,
+}
+
+struct BarState {
+    total: u64,
+    started: Instant,
+    drawn: Option<Instant>,
+}
+
+impl Bar {
+    pub fn new(message: String, visible: bool) -> Self {
+        Bar {
+            message,
+            visible,
+            state: Mutex::new(BarState {
+                total: 0,
+                started: Instant::now(),
+                drawn: None,
+            }),
+        }
+    }
+
+    fn draw(&self, line: String) {
+        let mut stdout = stdout().lock();
+        // Pad to clear what is left of a longer previous line
+        let _ = write!(stdout, "\r{line:<80}");
+        let _ = stdout.flush();
+    }
+}
+
+impl Progress for Bar {
+    fn start(&self, _stage: Stage, total: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.total = total;
+        state.started = Instant::now();
+        state.drawn = None;
+    }
+
+    fn advance(&self, _stage: Stage, done: u64) {
+        if !self.visible {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state
+            .drawn
+            .is_some_and(|drawn| drawn.elapsed() < REDRAW_INTERVAL)
+        {
+            return;
+        }
+        state.drawn = Some(Instant::now());
+        self.draw(format!(
+            "{} {}",
+            self.message,
+            bar(done, state.total, state.started.elapsed())
+        ));
+    }
+
+    fn finish(&self, _stage: Stage) {
+        if !self.visible {
+            return;
+        }
+        let state = self.state.lock().unwrap();
+        self.draw(format!(
+            "{} done in {:?}",
+            self.message,
+            state.started.elapsed()
+        ));
+        println!();
+    }
+}
+
+// Draws the bar, the percentage and the time left assuming the rest goes as fast as the part so far.
+fn bar(done: u64, total: u64, elapsed: Duration) -> String {
+    let done = done.min(total);
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        done as f64 / total as f64
+    };
+    let filled = (fraction * BAR_WIDTH as f64) as usize;
+    let eta = if done == 0 {
+        "?".to_string()
+    } else {
+        let left = elapsed.as_secs_f64() * (total - done) as f64 / done as f64;
+        format!("{}s", left.ceil())
+    };
+    format!(
+        "[{}{}] {:>3}% eta {eta}",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled),
+        (fraction * 100.0) as u32
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drawn_bar() {
+        assert_eq!(
+            format!("[{}{}]  50% eta 2s", "█".repeat(15), "░".repeat(15)),
+            bar(50, 100, Duration::from_secs(2))
+        );
+        assert_eq!(
+            format!("[{}]   0% eta ?", "░".repeat(30)),
+            bar(0, 100, Duration::ZERO)
+        );
+        // Solvers can expand more nodes than the maze has
+        assert_eq!(
+            format!("[{}] 100% eta 0s", "█".repeat(30)),
+            bar(120, 100, Duration::from_secs(1))
+        );
+    }
+}
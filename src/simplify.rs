@@ -0,0 +1,292 @@
+use std::{collections::VecDeque, time::Instant};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    maze::{Edge, Maze},
+    node::{Node, NodeType, Point},
+    statistics::Simplified,
+};
+
+// Passes that shrink the node graph before solving. None of them change the length of the
+// shortest path from the entrance to the exit. Only collapse can join two nodes by a second edge, so
+// dedupe does nothing without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pass {
+    /// Remove dead ends, over and over until the corridors leading to them are gone as well
+    Prune,
+    /// Replace every node with two neighbours by one edge around its corner
+    Collapse,
+    /// Remove parallel edges, keeping the shortest of those collapse joins two nodes with
+    Dedupe,
+}
+
+impl Maze {
+    // Runs the passes until none of them finds anything left to do. The entrance and the exit are
+    // always kept.
+    pub fn simplify(&mut self, passes: &[Pass]) -> Simplified {
+        let started = Instant::now();
+        let (nodes_before, edges_before) = (self.data.len(), self.edge_count());
+        let mut simplify = Simplify {
+            start: self.data.get(&NodeType::Start).map(|node| node.point),
+            exit: self.data.get(&NodeType::Exit).map(|node| node.point),
+            prune: passes.contains(&Pass::Prune),
+            collapse: passes.contains(&Pass::Collapse),
+            dedupe: passes.contains(&Pass::Dedupe),
+            maze: self,
+        };
+        let mut queue: VecDeque<Point> =
+            simplify.maze.data.values().map(|node| node.point).collect();
+        while let Some(point) = queue.pop_front() {
+            queue.extend(simplify.reduce(point));
+        }
+        Simplified {
+            passes: passes.to_vec(),
+            nodes_before,
+            edges_before,
+            nodes_after: self.data.len(),
+            edges_after: self.edge_count(),
+            duration: started.elapsed(),
+        }
+    }
+}
+
+struct Simplify<'a> {
+    maze: &'a mut Maze,
+    start: Option<Point>,
+    exit: Option<Point>,
+    prune: bool,
+    collapse: bool,
+    dedupe: bool,
+}
+
+impl Simplify<'_> {
+    // Removes the node at `point` if a pass allows it, returning the neighbours to look at again.
+    fn reduce(&mut self, point: Point) -> Vec<Point> {
+        if Some(point) == self.start || Some(point) == self.exit {
+            return Vec::new();
+        }
+        let Some(node) = self.maze.data.get(&NodeType::Path(point)) else {
+            return Vec::new();
+        };
+        let neighbours: Vec<Point> = node.children.values().copied().collect();
+        match neighbours[..] {
+            [] if self.prune => {
+                self.remove(point);
+                Vec::new()
+            }
+            [next] if self.prune => {
+                self.unlink(next, point);
+                self.remove(point);
+                vec![next]
+            }
+            [a, b] if self.collapse => self.collapse(a, point, b),
+            _ => Vec::new(),
+        }
+    }
+
+    // Joins `a` and `b` directly, around the corner at `point`.
+    fn collapse(&mut self, a: Point, point: Point, b: Point) -> Vec<Point> {
+        let length = self.maze.distance(a, point) + self.maze.distance(point, b);
+        let existing = self.maze.node_at(a).and_then(|node| {
+            node.children
+                .values()
+                .any(|next| *next == b)
+                .then(|| self.maze.distance(a, b))
+        });
+        match existing {
+            // The children can only hold one edge to every neighbour
+            Some(_) if !self.dedupe => return Vec::new(),
+            Some(shorter) if shorter <= length => {
+                self.unlink(a, point);
+                self.unlink(b, point);
+                self.remove(point);
+                return vec![a, b];
+            }
+            Some(_) => {
+                self.unlink(a, b);
+                self.unlink(b, a);
+            }
+            None => {}
+        }
+
+        let mut bends = self.maze.bends(a, point).to_vec();
+        bends.push(point);
+        bends.extend(self.maze.bends(point, b));
+        self.relink(a, point, b);
+        self.relink(b, point, a);
+        self.maze.edges.insert(
+            (a, b),
+            Edge {
+                length,
+                bends: bends.clone(),
+            },
+        );
+        bends.reverse();
+        self.maze.edges.insert((b, a), Edge { length, bends });
+        self.remove(point);
+        vec![a, b]
+    }
+
+    fn node_mut(&mut self, point: Point) -> Option<&mut Node> {
+        let key = if Some(point) == self.start {
+            NodeType::Start
+        } else if Some(point) == self.exit {
+            NodeType::Exit
+        } else {
+            NodeType::Path(point)
+        };
+        self.maze.data.get_mut(&key)
+    }
+
+    // Drops the edge from `from` to `to`.
+    fn unlink(&mut self, from: Point, to: Point) {
+        if let Some(node) = self.node_mut(from) {
+            node.children.retain(|_, next| *next != to);
+        }
+        self.maze.edges.remove(&(from, to));
+    }
+
+    // Points the edge from `from` to `old` at `new` instead, leaving it in the same direction.
+    fn relink(&mut self, from: Point, old: Point, new: Point) {
+        if let Some(node) = self.node_mut(from) {
+            for next in node.children.values_mut() {
+                if *next == old {
+                    *next = new;
+                }
+            }
+        }
+        self.maze.edges.remove(&(from, old));
+    }
+
+    fn remove(&mut self, point: Point) {
+        if let Some(node) = self.maze.data.remove(&NodeType::Path(point)) {
+            for next in node.children.values() {
+                self.maze.edges.remove(&(point, *next));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::algorithms::{dijkstra::Dijkstra, left_turn::LeftTurn, Algorithm, Solution, Solver};
+    use crate::generator::{generate, GeneratorOptions};
+    use crate::img::Image;
+    use crate::maze::PATHWAY;
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+    use crate::utils::get_dist;
+    use image::Pixel;
+    use pretty_assertions::assert_eq;
+
+    // The solution walks from the entrance to the exit one open pixel at a time.
+    fn walks_the_maze(image: &Image, solution: &Solution) -> bool {
+        let pixels = solution.pixels();
+        let open = |point: &Point| image.image.get_pixel(point.x, point.y).channels() == PATHWAY;
+        pixels.iter().all(open)
+            && pixels
+                .windows(2)
+                .all(|pair| get_dist(&pair[0], &pair[1]) == 1)
+            && pixels.first().is_some_and(|point| point.y == 0)
+            && pixels
+                .last()
+                .is_some_and(|point| point.y == image.image.height() - 1)
+    }
+
+    #[test]
+    fn reduces_the_graph() {
+        let image = maze_image!([
+            [0, 0, 0, 1, 0, 0, 0, 0, 0],
+            [0, 1, 1, 1, 1, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0, 1, 0],
+            [0, 1, 0, 1, 1, 1, 0, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 1, 1, 1, 1, 0],
+            [0, 0, 0, 0, 0, 1, 0, 0, 0]
+        ]);
+        let original = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let expected = Dijkstra.solve(&original).unwrap();
+
+        let mut maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let simplified = maze.simplify(&[Pass::Prune, Pass::Collapse, Pass::Dedupe]);
+        assert_eq!(
+            (original.data.len(), original.edge_count()),
+            (simplified.nodes_before, simplified.edges_before)
+        );
+        // The dead end goes, and only the shortest of the ways around the walls is left
+        assert_eq!((2, 1), (simplified.nodes_after, simplified.edges_after));
+        assert_eq!(maze.data.len(), simplified.nodes_after);
+
+        let solution = Dijkstra.solve(&maze).unwrap();
+        assert_eq!(2, solution.length);
+        assert_eq!(expected.distance(), solution.distance());
+        assert_eq!(expected.pixels(), solution.pixels());
+        assert!(walks_the_maze(&image, &solution));
+    }
+
+    #[test]
+    fn unreachable_exit() {
+        // The entrance only leads to a dead end, which pruning removes
+        let image = maze_image!([
+            [0, 0, 0, 1, 0, 0, 0],
+            [0, 1, 1, 1, 0, 0, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 1, 0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 0, 1, 0]
+        ]);
+        let passes = [
+            vec![Pass::Prune],
+            vec![Pass::Collapse],
+            vec![Pass::Prune, Pass::Collapse],
+            vec![Pass::Prune, Pass::Collapse, Pass::Dedupe],
+        ];
+        for passes in &passes {
+            let mut maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+            maze.simplify(passes);
+            for algorithm in Algorithm::all() {
+                assert!(algorithm.solve(&maze).is_none(), "{passes:?}, {algorithm}");
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_the_shortest_paths() {
+        let passes = [
+            vec![Pass::Prune],
+            vec![Pass::Collapse],
+            vec![Pass::Prune, Pass::Collapse],
+            vec![Pass::Prune, Pass::Collapse, Pass::Dedupe],
+        ];
+        for seed in 0..20 {
+            let image = generate(&GeneratorOptions {
+                width: 3 + seed as u32 % 6,
+                height: 3 + seed as u32 % 5,
+                seed,
+                braid: 0.5,
+            });
+            let original = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+            let expected = Dijkstra.solve(&original).unwrap().distance();
+            for passes in &passes {
+                let mut maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+                let simplified = maze.simplify(passes);
+                assert!(simplified.nodes_after <= simplified.nodes_before);
+
+                for algorithm in Algorithm::all() {
+                    let solution = algorithm.solve(&maze).unwrap();
+                    assert!(
+                        walks_the_maze(&image, &solution),
+                        "maze {seed}, {passes:?}, {algorithm}"
+                    );
+                }
+                let solution = Dijkstra.solve(&maze).unwrap();
+                assert_eq!(expected, solution.distance(), "maze {seed}, {passes:?}");
+                assert!(LeftTurn::default().solve(&maze).is_some());
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::time::Duration;
 
-use crate::algorithms::Algorithm;
+use serde::{Serialize, Serializer};
 
-#[derive(Debug, Default)]
+use crate::{algorithms::Algorithm, simplify::Pass};
+
+#[derive(Debug, Default, Serialize)]
 pub struct Statistics<'a> {
     pub img: ImageStats,
     pub alg: AlgStats,
@@ -10,30 +12,53 @@ pub struct Statistics<'a> {
     pub general: GeneralStats<'a>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct ImageStats {
     pub width: u32,
     pub height: u32,
     pub total: u32,
+    #[serde(rename = "load_duration_ms", serialize_with = "millis")]
     pub load_duration: Duration,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct MazeStats {
     pub total_nodes: u32,
     pub dead_ends: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simplified: Option<Simplified>,
+}
+
+// The node graph before and after the simplification passes.
+#[derive(Debug, Default, Serialize)]
+pub struct Simplified {
+    pub passes: Vec<Pass>,
+    pub nodes_before: usize,
+    pub edges_before: usize,
+    pub nodes_after: usize,
+    pub edges_after: usize,
+    #[serde(rename = "duration_ms", serialize_with = "millis")]
+    pub duration: Duration,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct AlgStats {
-    pub algorithm: Algorithm,
+    pub algorithm: Option<Algorithm>,
     pub decisions: u32,
+    pub expanded: u32,
     pub solution_length: u32,
+    #[serde(rename = "solution_time_ms", serialize_with = "millis")]
     pub solution_time: Duration,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct GeneralStats<'a> {
     pub filename: &'a str,
+    #[serde(rename = "time_total_ms", serialize_with = "millis")]
     pub time_total: Duration,
 }
+
+// Durations are written as fractional milliseconds, easier to consume than serde's secs and nanos.
+fn millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_nanos() as f64 / 1e6)
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+use image::{Pixel, Rgb};
+
+use crate::{
+    algorithms::Solution,
+    colors::PathColor,
+    img::{Image, Region},
+    maze::{PATHWAY, WALL},
+};
+
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    pub scale: u32,
+    pub wall: Rgb<u8>,
+    pub path: Rgb<u8>,
+    pub solution: PathColor,
+    pub stroke_width: f32,
+    pub crop: Option<Region>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            wall: Rgb(WALL),
+            path: Rgb(PATHWAY),
+            solution: PathColor::default(),
+            stroke_width: 1.0,
+            crop: None,
+        }
+    }
+}
+
+fn hex(color: Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+// Walls as (x, y, width) runs, one rectangle per consecutive stretch of wall pixels in a row.
+fn wall_runs(image: &Image) -> Vec<(u32, u32, u32)> {
+    let mut runs = Vec::new();
+    for (y, row) in image.image.rows().enumerate() {
+        let mut start = None;
+        for (x, pixel) in row.enumerate() {
+            match (pixel.channels() == PATHWAY, start) {
+                (false, None) => start = Some(x as u32),
+                (true, Some(s)) => {
+                    runs.push((s, y as u32, x as u32 - s));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            runs.push((s, y as u32, image.image.width() - s));
+        }
+    }
+    runs
+}
+
+// Renders the maze, and the solution if given, as an svg document where every pixel is one
+// unit in the view box. Cropping only narrows the view box.
+pub fn render(
+    image: &Image,
+    solution: Option<&Solution>,
+    options: &SvgOptions,
+) -> Result<String, String> {
+    let (width, height) = image.image.dimensions();
+    let view = options.crop.unwrap_or(Region {
+        x: 0,
+        y: 0,
+        width,
+        height,
+    });
+    // Only the size attributes are scaled, the drawing stays one unit per pixel
+    view.check(width, height, 1)?;
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        view.width as u64 * options.scale as u64,
+        view.height as u64 * options.scale as u64,
+        view.x,
+        view.y,
+        view.width,
+        view.height,
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+        hex(options.path)
+    )
+    .unwrap();
+
+    writeln!(
+        svg,
+        r#"<g fill="{}" shape-rendering="crispEdges">"#,
+        hex(options.wall)
+    )
+    .unwrap();
+    for (x, y, w) in wall_runs(image) {
+        writeln!(svg, r#"<rect x="{x}" y="{y}" width="{w}" height="1"/>"#).unwrap();
+    }
+    svg.push_str("</g>\n");
+
+    match (solution, &options.solution) {
+        (Some(solution), PathColor::Solid(color)) => {
+            let points: Vec<String> = solution
+                .points()
+                .iter()
+                .map(|point| format!("{}.5,{}.5", point.x, point.y))
+                .collect();
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linecap="square" stroke-linejoin="miter"/>"#,
+                points.join(" "),
+                hex(*color),
+                options.stroke_width,
+            )
+            .unwrap();
+        }
+        // One line per step, colored along the path like the raster output
+        (Some(solution), color) => {
+            let pixels = solution.pixels();
+            let last = pixels.len().saturating_sub(1).max(1) as f32;
+            writeln!(
+                svg,
+                r#"<g stroke-width="{}" stroke-linecap="square">"#,
+                options.stroke_width
+            )
+            .unwrap();
+            for (i, pair) in pixels.windows(2).enumerate() {
+                writeln!(
+                    svg,
+                    r#"<line x1="{}.5" y1="{}.5" x2="{}.5" y2="{}.5" stroke="{}"/>"#,
+                    pair[0].x,
+                    pair[0].y,
+                    pair[1].x,
+                    pair[1].y,
+                    hex(color.at(i as f32 / last)),
+                )
+                .unwrap();
+            }
+            svg.push_str("</g>\n");
+        }
+        (None, _) => {}
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::maze_image;
+    use crate::{
+        algorithms::{breadth_first::BreadthFirst, Solver},
+        maze::Maze,
+        statistics::Statistics,
+    };
+
+    #[test]
+    fn merged_wall_rows() {
+        let img = maze_image!([[0, 0, 1, 0, 0], [0, 1, 1, 1, 0], [0, 0, 0, 1, 0]]);
+        assert_eq!(
+            vec![
+                (0, 0, 2),
+                (3, 0, 2),
+                (0, 1, 1),
+                (4, 1, 1),
+                (0, 2, 3),
+                (4, 2, 1)
+            ],
+            wall_runs(&img)
+        );
+    }
+
+    #[test]
+    fn solution_polyline() {
+        let img = maze_image!([[0, 1, 0, 0, 0], [0, 1, 1, 1, 0], [0, 0, 0, 1, 0]]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = BreadthFirst.solve(&maze).unwrap();
+
+        let svg = render(&img, Some(&solution), &SvgOptions::default()).unwrap();
+        assert!(svg.contains(r#"<polyline points="1.5,0.5 1.5,1.5 3.5,1.5 3.5,2.5""#));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="5" height="3""#));
+    }
+
+    #[test]
+    fn solution_gradient() {
+        let img = maze_image!([[0, 1, 0, 0, 0], [0, 1, 1, 1, 0], [0, 0, 0, 1, 0]]);
+        let maze = Maze::from_image(&img, &mut Statistics::default()).unwrap();
+        let solution = BreadthFirst.solve(&maze).unwrap();
+
+        let options = SvgOptions {
+            solution: PathColor::Gradient(Rgb([0, 0, 0]), Rgb([0, 0, 200])),
+            ..SvgOptions::default()
+        };
+        let svg = render(&img, Some(&solution), &options).unwrap();
+        assert!(!svg.contains("<polyline"));
+        assert_eq!(4, svg.matches("<line").count());
+        assert!(svg.contains(r##"<line x1="1.5" y1="0.5" x2="1.5" y2="1.5" stroke="#000000"/>"##));
+        assert!(svg.contains(r##"<line x1="3.5" y1="1.5" x2="3.5" y2="2.5" stroke="#000096"/>"##));
+    }
+}
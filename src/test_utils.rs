@@ -0,0 +1,24 @@
+use image::Rgb;
+
+pub const WALL: Rgb<u8> = Rgb([0, 0, 0]);
+pub const PATH: Rgb<u8> = Rgb([255, 255, 255]);
+
+// Builds a maze image from rows of 1 for path and 0 for wall.
+macro_rules! maze_image {
+    ($num:expr) => {{
+        let mut image = image::RgbImage::new($num[0].len() as u32, $num.len() as u32);
+        for (y, row) in $num.iter().enumerate() {
+            for (x, item) in row.iter().enumerate() {
+                let pixel = if *item == 1 {
+                    $crate::test_utils::PATH
+                } else {
+                    $crate::test_utils::WALL
+                };
+                image.put_pixel(x as u32, y as u32, pixel);
+            }
+        }
+        $crate::img::Image { image }
+    }};
+}
+
+pub(crate) use maze_image;
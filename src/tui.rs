@@ -0,0 +1,492 @@
+use std::{
+    collections::HashSet,
+    env,
+    time::{Duration, Instant},
+};
+
+use image::Pixel;
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
+    style::Color,
+    text::Line,
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    algorithms::{
+        steps::{Event, Steps},
+        Algorithm,
+    },
+    img::{line, Image},
+    maze::{Maze, PATHWAY},
+    node::Point,
+};
+
+const STATS_WIDTH: u16 = 32;
+// Zoom levels are powers of two, negative ones give every pixel several cells
+const MIN_ZOOM: i32 = -3;
+const MAX_ZOOM: i32 = 16;
+const MAX_SPEED: u32 = 1 << 20;
+// A block of pixels in a zoomed out cell is sampled at most this many times in each direction
+const SAMPLES: u32 = 4;
+
+// What the solver did to a pixel, in the order they win when a cell covers several pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Mark {
+    None,
+    Visited,
+    Frontier,
+    Current,
+    Solution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Running,
+    Paused,
+    Solved(usize),
+    NoSolution,
+}
+
+// Colors of the marks, in full color when the terminal says it supports it and the basic ones of
+// any terminal otherwise.
+struct Palette {
+    wall: Color,
+    path: Color,
+    visited: Color,
+    frontier: Color,
+    current: Color,
+    solution: Color,
+    true_color: bool,
+}
+
+impl Palette {
+    fn detect() -> Self {
+        let true_color = env::var("COLORTERM")
+            .is_ok_and(|value| value.contains("truecolor") || value.contains("24bit"));
+        if true_color {
+            Palette {
+                wall: Color::Rgb(20, 20, 20),
+                path: Color::Rgb(230, 230, 230),
+                visited: Color::Rgb(70, 130, 180),
+                frontier: Color::Rgb(255, 165, 0),
+                current: Color::Rgb(255, 255, 0),
+                solution: Color::Rgb(220, 20, 60),
+                true_color,
+            }
+        } else {
+            Palette {
+                wall: Color::Black,
+                path: Color::White,
+                visited: Color::Blue,
+                frontier: Color::Yellow,
+                current: Color::LightYellow,
+                solution: Color::Red,
+                true_color,
+            }
+        }
+    }
+
+    fn mark(&self, mark: Mark) -> Option<Color> {
+        match mark {
+            Mark::None => None,
+            Mark::Visited => Some(self.visited),
+            Mark::Frontier => Some(self.frontier),
+            Mark::Current => Some(self.current),
+            Mark::Solution => Some(self.solution),
+        }
+    }
+}
+
+// Part of the maze in view, `x` and `y` are the pixel in the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct View {
+    x: i64,
+    y: i64,
+    zoom: i32,
+}
+
+impl View {
+    // Pixels per cell as a fraction, a cell is `num / den` pixels wide and half a cell high.
+    fn scale(&self) -> (i64, i64) {
+        (1 << self.zoom.max(0), 1 << (-self.zoom).max(0))
+    }
+}
+
+// Watches a solver run, keeping track of what it did to every pixel.
+pub struct App<'a> {
+    image: &'a Image,
+    maze: &'a Maze,
+    algorithm: Algorithm,
+    steps: Steps<'a>,
+    marks: Vec<Mark>,
+    frontier: HashSet<Point>,
+    expanded: u32,
+    events: u64,
+    current: Option<Point>,
+    solving: Duration,
+    paused: bool,
+    pub speed: u32,
+    view: View,
+    fitted: bool,
+    palette: Palette,
+    pub quit: bool,
+}
+
+impl<'a> App<'a> {
+    pub fn new(image: &'a Image, maze: &'a Maze, algorithm: Algorithm, speed: u32) -> Self {
+        let (width, height) = image.image.dimensions();
+        App {
+            image,
+            maze,
+            steps: algorithm.steps(maze),
+            algorithm,
+            marks: vec![Mark::None; (width * height) as usize],
+            frontier: HashSet::new(),
+            expanded: 0,
+            events: 0,
+            current: None,
+            solving: Duration::ZERO,
+            paused: false,
+            speed: speed.clamp(1, MAX_SPEED),
+            view: View {
+                x: 0,
+                y: 0,
+                zoom: 0,
+            },
+            fitted: false,
+            palette: Palette::detect(),
+            quit: false,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        if self.steps.is_finished() {
+            return match self.steps.solution() {
+                Some(solution) => State::Solved(solution.pixels().len()),
+                None => State::NoSolution,
+            };
+        }
+        if self.paused {
+            State::Paused
+        } else {
+            State::Running
+        }
+    }
+
+    pub fn decisions(&self) -> u32 {
+        self.steps.decisions()
+    }
+
+    // Runs the solver for another frame, at the current speed.
+    pub fn tick(&mut self) {
+        if !self.paused {
+            self.advance(self.speed);
+        }
+    }
+
+    // Takes up to `count` events from the solver.
+    fn advance(&mut self, count: u32) {
+        let start = Instant::now();
+        for _ in 0..count {
+            let Some(event) = self.steps.next() else {
+                self.finish();
+                break;
+            };
+            self.apply(event);
+        }
+        self.solving += start.elapsed();
+    }
+
+    fn apply(&mut self, event: Event) {
+        self.events += 1;
+        match event {
+            Event::Expanded(point) | Event::Found(point) => {
+                self.expanded += 1;
+                self.frontier.remove(&point);
+                if let Some(previous) = self.current {
+                    // Walkers like left turn don't push, draw the corridor they went through
+                    if self.connected(previous, point) {
+                        self.mark_line(previous, point, Mark::Visited);
+                    }
+                    self.mark(previous, Mark::Visited);
+                }
+                self.mark(point, Mark::Current);
+                self.current = Some(point);
+            }
+            Event::Pushed(point) => {
+                self.frontier.insert(point);
+                if let Some(parent) = self.current {
+                    self.mark_line(parent, point, Mark::Visited);
+                    self.mark(parent, Mark::Current);
+                }
+                self.mark(point, Mark::Frontier);
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(current) = self.current {
+            self.mark(current, Mark::Visited);
+        }
+        if let Some(solution) = self.steps.solution() {
+            for point in solution.pixels() {
+                self.mark(point, Mark::Solution);
+            }
+        }
+    }
+
+    fn connected(&self, a: Point, b: Point) -> bool {
+        self.maze
+            .node_at(a)
+            .is_some_and(|node| node.children.values().any(|child| *child == b))
+    }
+
+    fn mark(&mut self, point: Point, mark: Mark) {
+        let index = (point.y * self.image.image.width() + point.x) as usize;
+        if let Some(current) = self.marks.get_mut(index) {
+            // The solution is final, anything else is replaced by what happened last
+            if *current != Mark::Solution {
+                *current = mark;
+            }
+        }
+    }
+
+    fn mark_line(&mut self, from: Point, to: Point, mark: Mark) {
+        for point in line(&from, &to) {
+            let index = (point.y * self.image.image.width() + point.x) as usize;
+            if self.marks.get(index).is_some_and(|current| *current < mark) {
+                self.marks[index] = mark;
+            }
+        }
+    }
+
+    pub fn handle(&mut self, key: KeyCode) {
+        let (num, den) = self.view.scale();
+        // Pan a few cells at a time, more when zoomed out
+        let pan = (8 * num / den).max(1);
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('n') => {
+                self.paused = true;
+                self.advance(1);
+            }
+            KeyCode::Char(']') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            KeyCode::Char('[') => self.speed = (self.speed / 2).max(1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom(-1),
+            KeyCode::Char('-') => self.zoom(1),
+            KeyCode::Char('0') => self.fitted = false,
+            KeyCode::Left | KeyCode::Char('h') => self.view.x -= pan,
+            KeyCode::Right | KeyCode::Char('l') => self.view.x += pan,
+            KeyCode::Up | KeyCode::Char('k') => self.view.y -= pan,
+            KeyCode::Down | KeyCode::Char('j') => self.view.y += pan,
+            _ => {}
+        }
+    }
+
+    fn zoom(&mut self, by: i32) {
+        self.view.zoom = (self.view.zoom + by).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    // Picks the closest zoom that shows the whole maze and puts it in the top left corner.
+    fn fit(&mut self, area: Rect) {
+        let (width, height) = self.image.image.dimensions();
+        let (columns, rows) = (area.width as i64, area.height as i64 * 2);
+        let mut view = View {
+            x: 0,
+            y: 0,
+            zoom: MIN_ZOOM,
+        };
+        while view.zoom < MAX_ZOOM {
+            let (num, den) = view.scale();
+            if width as i64 * den <= columns * num && height as i64 * den <= rows * num {
+                break;
+            }
+            view.zoom += 1;
+        }
+        self.view = view;
+        self.fitted = true;
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [maze_area, stats_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(STATS_WIDTH)])
+                .areas(frame.area());
+
+        let block = Block::bordered().title(format!(" {} ", self.algorithm));
+        let inner = block.inner(maze_area);
+        frame.render_widget(block, maze_area);
+        if !self.fitted {
+            self.fit(inner);
+        }
+        self.draw_maze(inner, frame.buffer_mut());
+
+        frame.render_widget(
+            Paragraph::new(self.stats()).block(Block::bordered().title(" statistics ")),
+            stats_area,
+        );
+    }
+
+    // Every cell shows two pixels on top of each other with a half block, the top one as the
+    // foreground and the bottom one as the background.
+    fn draw_maze(&self, area: Rect, buffer: &mut Buffer) {
+        for row in 0..area.height {
+            for column in 0..area.width {
+                let top = self.color(column as i64, row as i64 * 2);
+                let bottom = self.color(column as i64, row as i64 * 2 + 1);
+                if let Some(cell) = buffer.cell_mut((area.x + column, area.y + row)) {
+                    cell.set_char('▀')
+                        .set_fg(top.unwrap_or(Color::Reset))
+                        .set_bg(bottom.unwrap_or(Color::Reset));
+                }
+            }
+        }
+    }
+
+    // Color of the pixels under a half cell, nothing when it is outside of the maze.
+    fn color(&self, column: i64, row: i64) -> Option<Color> {
+        let (width, height) = self.image.image.dimensions();
+        let (num, den) = self.view.scale();
+        let x = self.view.x + column * num / den;
+        let y = self.view.y + row * num / den;
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            return None;
+        }
+
+        // Zoomed out a cell covers a block of pixels, which shows the most important mark in it
+        // or else whether it is mostly path or wall
+        let step = (num / SAMPLES as i64).max(1) as usize;
+        let (mut mark, mut paths, mut samples) = (Mark::None, 0, 0);
+        for y in (y..(y + num).min(height as i64)).step_by(step) {
+            for x in (x..(x + num).min(width as i64)).step_by(step) {
+                let (x, y) = (x as u32, y as u32);
+                mark = mark.max(self.marks[(y * width + x) as usize]);
+                if self.image.image.get_pixel(x, y).channels() == PATHWAY {
+                    paths += 1;
+                }
+                samples += 1;
+            }
+        }
+        Some(self.palette.mark(mark).unwrap_or(if paths * 2 >= samples {
+            self.palette.path
+        } else {
+            self.palette.wall
+        }))
+    }
+
+    fn stats(&self) -> Vec<Line<'static>> {
+        let state = match self.state() {
+            State::Running => "running".to_string(),
+            State::Paused => "paused".to_string(),
+            State::Solved(length) => format!("solved, {length} pixels"),
+            State::NoSolution => "no solution".to_string(),
+        };
+        let (num, den) = self.view.scale();
+        let zoom = if num >= den {
+            format!("1:{num}")
+        } else {
+            format!("{den}:1")
+        };
+        let (width, height) = self.image.image.dimensions();
+        vec![
+            Line::from(format!("state      {state}")),
+            Line::from(format!("decisions  {}", self.decisions())),
+            Line::from(format!("expanded   {}", self.expanded)),
+            Line::from(format!("frontier   {}", self.frontier.len())),
+            Line::from(format!("events     {}", self.events)),
+            Line::from(format!("time       {:.1?}", self.solving)),
+            Line::from(format!("speed      {}/frame", self.speed)),
+            Line::from(""),
+            Line::from(format!("maze       {width}x{height}")),
+            Line::from(format!("nodes      {}", self.maze.data.len())),
+            Line::from(format!("view       {},{} {zoom}", self.view.x, self.view.y)),
+            Line::from(format!(
+                "colors     {}",
+                if self.palette.true_color {
+                    "24 bit"
+                } else {
+                    "basic"
+                }
+            )),
+            Line::from(""),
+            Line::from("space  pause / resume"),
+            Line::from("n      step"),
+            Line::from("[ ]    slower / faster"),
+            Line::from("+ -    zoom in / out"),
+            Line::from("arrows pan, 0 fit"),
+            Line::from("q      quit"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::statistics::Statistics;
+    use crate::test_utils::maze_image;
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn image() -> Image {
+        maze_image!([
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 1, 0],
+            [0, 1, 1, 1, 0],
+            [0, 1, 0, 0, 0],
+            [0, 1, 0, 0, 0]
+        ])
+    }
+
+    #[test]
+    fn runs_to_the_solution() {
+        let image = image();
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let mut app = App::new(&image, &maze, "breadth-first".parse().unwrap(), 1);
+
+        app.handle(KeyCode::Char('n'));
+        assert_eq!(State::Paused, app.state());
+        assert_eq!(1, app.events);
+        assert_eq!(Mark::Current, app.marks[1]);
+
+        app.handle(KeyCode::Char(' '));
+        app.handle(KeyCode::Char(']'));
+        assert_eq!(2, app.speed);
+        while app.state() == State::Running {
+            app.tick();
+        }
+        assert_eq!(State::Solved(5), app.state());
+        // The solution runs down the left corridor, the dead end to the right was only visited
+        assert_eq!(Mark::Solution, app.marks[(3 * 5 + 1) as usize]);
+        assert_eq!(Mark::Visited, app.marks[(2 * 5 + 2) as usize]);
+    }
+
+    #[test]
+    fn half_blocks() {
+        let image = image();
+        let maze = Maze::from_image(&image, &mut Statistics::default()).unwrap();
+        let mut app = App::new(&image, &maze, "a-star".parse().unwrap(), 1);
+        app.palette = Palette {
+            true_color: false,
+            ..Palette::detect()
+        };
+        app.palette.wall = Color::Black;
+        app.palette.path = Color::White;
+
+        let mut terminal = Terminal::new(TestBackend::new(STATS_WIDTH + 12, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        // The 5x5 maze fits twice over in the 10x22 inner area, so every pixel is 2x2 half cells
+        assert_eq!(-1, app.view.zoom);
+        let buffer = terminal.backend().buffer();
+        let cell = &buffer[(1 + 2, 1)];
+        assert_eq!("▀", cell.symbol());
+        assert_eq!((Color::White, Color::White), (cell.fg, cell.bg));
+        let cell = &buffer[(1, 1)];
+        assert_eq!((Color::Black, Color::Black), (cell.fg, cell.bg));
+        // Past the maze the terminal shows through
+        assert_eq!(Color::Reset, buffer[(1, 10)].bg);
+    }
+}
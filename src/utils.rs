@@ -2,9 +2,9 @@ use image::{Pixel, RgbImage};
 
 use crate::{
     algorithms::Algorithm,
+    cli::LegacyAlgorithmArgs,
     maze::{Maze, PATHWAY, WALL},
     node::{Node, NodeType, Point},
-    Cli,
 };
 
 pub fn look_ahead(x: u32, y: u32, image: &RgbImage) -> bool {
@@ -39,20 +39,21 @@ pub fn is_wall(x: u32, y: u32, image: &RgbImage) -> bool {
         .is_some_and(|pix| pix.channels() == WALL)
 }
 
-pub fn get_algorithm(cli: &Cli) -> Algorithm {
-    if cli.dijkstra {
-        Algorithm::Dijkstra
+pub fn get_algorithm(cli: &LegacyAlgorithmArgs) -> Option<Algorithm> {
+    let name = if cli.dijkstra {
+        "dijkstra"
     } else if cli.a_star {
-        Algorithm::AStar
+        "a-star"
     } else if cli.left_turn {
-        Algorithm::LeftTurn
+        "left-turn"
     } else if cli.breadth_first {
-        Algorithm::BreadthFirst
+        "breadth-first"
     } else if cli.depth_first {
-        Algorithm::DepthFirst
+        "depth-first"
     } else {
-        Algorithm::None
-    }
+        return None;
+    };
+    name.parse().ok()
 }
 
 pub fn get_dist(current: &Point, next: &Point) -> u32 {